@@ -36,6 +36,9 @@ use torii_core::processors::store_transaction::StoreTransactionProcessor;
 use torii_core::simple_broker::SimpleBroker;
 use torii_core::sql::Sql;
 use torii_core::types::Model;
+use torii_graphql::types::{
+    DatetimeFormat, EntityNames, FieldCasing, ListBatchSize, ListCompression, RowCap,
+};
 use torii_server::proxy::Proxy;
 use tracing::{error, info};
 use tracing_subscriber::{fmt, EnvFilter};
@@ -113,6 +116,38 @@ struct Args {
     /// Enable indexing pending blocks
     #[arg(long)]
     index_pending: bool,
+
+    /// Format the datetimes sent by the GraphQL entity subscriptions as RFC 3339 timestamps,
+    /// with an explicit UTC offset
+    #[arg(long)]
+    graphql_rfc3339_datetimes: bool,
+
+    /// Maximum number of rows returned by a GraphQL connection, larger requests being truncated
+    #[arg(long, default_value = "1000")]
+    graphql_max_rows: u64,
+
+    /// Name of the GraphQL query fetching one entity, `entity` by default
+    #[arg(long, value_name = "NAME")]
+    graphql_entity_name: Option<String>,
+
+    /// Name of the GraphQL query fetching many entities, `entities` by default
+    #[arg(long, value_name = "NAME")]
+    graphql_entities_name: Option<String>,
+
+    /// Name the GraphQL fields of the models in camelCase rather than after their snake_case
+    /// Cairo members
+    #[arg(long)]
+    graphql_camel_case_fields: bool,
+
+    /// Add a `_compressed` field to the lists of scalars of the models, holding their JSON,
+    /// compressed when larger than the given number of bytes. The lists are not compressed by
+    /// default
+    #[arg(long, value_name = "BYTES")]
+    graphql_compress_lists_above: Option<usize>,
+
+    /// Number of rows of the lists of the models fetched at once by the GraphQL queries
+    #[arg(long, default_value = "1000")]
+    graphql_list_batch_size: u64,
 }
 
 #[tokio::main]
@@ -212,10 +247,27 @@ async fn main() -> anyhow::Result<()> {
 
     let proxy_server = Arc::new(Proxy::new(args.addr, args.allowed_origins, Some(grpc_addr), None));
 
+    let datetime_format = if args.graphql_rfc3339_datetimes {
+        DatetimeFormat::Rfc3339
+    } else {
+        DatetimeFormat::Legacy
+    };
+
+    let entity_names = EntityNames::new(args.graphql_entity_name, args.graphql_entities_name)?;
+
+    let field_casing =
+        if args.graphql_camel_case_fields { FieldCasing::Camel } else { FieldCasing::Snake };
+
     let graphql_server = spawn_rebuilding_graphql_server(
         shutdown_tx.clone(),
         pool.into(),
         args.external_url,
+        datetime_format,
+        RowCap(args.graphql_max_rows),
+        entity_names,
+        field_casing,
+        ListCompression(args.graphql_compress_lists_above),
+        ListBatchSize(args.graphql_list_batch_size),
         proxy_server.clone(),
     );
 
@@ -258,18 +310,35 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn spawn_rebuilding_graphql_server(
     shutdown_tx: Sender<()>,
     pool: Arc<SqlitePool>,
     external_url: Option<Url>,
+    datetime_format: DatetimeFormat,
+    row_cap: RowCap,
+    entity_names: EntityNames,
+    field_casing: FieldCasing,
+    list_compression: ListCompression,
+    list_batch_size: ListBatchSize,
     proxy_server: Arc<Proxy>,
 ) {
     let mut broker = SimpleBroker::<Model>::subscribe();
 
     loop {
         let shutdown_rx = shutdown_tx.subscribe();
-        let (new_addr, new_server) =
-            torii_graphql::server::new(shutdown_rx, &pool, external_url.clone()).await;
+        let (new_addr, new_server) = torii_graphql::server::new(
+            shutdown_rx,
+            &pool,
+            external_url.clone(),
+            datetime_format,
+            row_cap,
+            entity_names.clone(),
+            field_casing,
+            list_compression,
+            list_batch_size,
+        )
+        .await;
 
         tokio::spawn(new_server);
 
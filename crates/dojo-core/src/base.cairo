@@ -2,7 +2,7 @@ use dojo::world::IWorldDispatcher;
 
 #[starknet::contract]
 mod base {
-    use starknet::{ClassHash, get_caller_address};
+    use starknet::{ClassHash, ContractAddress, get_caller_address};
     use dojo::world::IWorldDispatcher;
     use dojo::world::IWorldProvider;
 
@@ -34,6 +34,10 @@ mod base {
         fn world(self: @ContractState) -> IWorldDispatcher {
             self.world_dispatcher.read()
         }
+
+        fn world_address(self: @ContractState) -> ContractAddress {
+            self.world_dispatcher.read().contract_address
+        }
     }
 
     #[abi(embed_v0)]
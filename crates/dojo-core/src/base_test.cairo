@@ -36,6 +36,10 @@ mod contract_upgrade {
         fn world(self: @ContractState) -> IWorldDispatcher {
             IWorldDispatcher { contract_address: starknet::contract_address_const::<'world'>() }
         }
+
+        fn world_address(self: @ContractState) -> starknet::ContractAddress {
+            starknet::contract_address_const::<'world'>()
+        }
     }
 }
 
@@ -44,6 +44,7 @@ trait IUpgradeableWorld<T> {
 #[starknet::interface]
 trait IWorldProvider<T> {
     fn world(self: @T) -> IWorldDispatcher;
+    fn world_address(self: @T) -> ContractAddress;
 }
 
 #[starknet::interface]
@@ -51,6 +52,28 @@ trait IDojoResourceProvider<T> {
     fn dojo_resource(self: @T) -> felt252;
 }
 
+#[starknet::interface]
+trait IContractVersionProvider<T> {
+    fn contract_version(self: @T) -> ByteArray;
+}
+
+#[starknet::interface]
+trait IDojoVersionProvider<T> {
+    fn dojo_version(self: @T) -> felt252;
+}
+
+#[starknet::interface]
+trait IDependenciesProvider<T> {
+    fn dependencies(self: @T) -> Array<felt252>;
+}
+
+#[starknet::interface]
+trait IPausable<T> {
+    fn pause(ref self: T);
+    fn unpause(ref self: T);
+    fn is_paused(self: @T) -> bool;
+}
+
 mod Errors {
     const METADATA_DESER: felt252 = 'metadata deser error';
     const NOT_OWNER: felt252 = 'not owner';
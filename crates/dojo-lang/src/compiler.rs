@@ -286,6 +286,7 @@ fn update_manifest(
     let mut models = BTreeMap::new();
     let mut contracts = BTreeMap::new();
     let mut computed = BTreeMap::new();
+    let mut writes = BTreeMap::new();
 
     if let Some(external_contracts) = external_contracts {
         let external_crate_ids = collect_external_crate_ids(db, external_contracts);
@@ -320,6 +321,7 @@ fn update_manifest(
                         *module_id,
                         &compiled_artifacts,
                     )?);
+                    writes.extend(get_dojo_system_writes(db, dojo_aux_data, *module_id));
                 }
             }
         }
@@ -331,6 +333,19 @@ fn update_manifest(
         contract_data.0.inner.computed = computed_value_entrypoint;
     });
 
+    // the models written by the systems are merged with the ones found by the semantic analysis
+    writes.into_iter().for_each(|(contract, system_writes)| {
+        if let Some((manifest, _)) = contracts.get_mut(contract.as_str()) {
+            let contract_writes = std::mem::take(&mut manifest.inner.writes);
+            manifest.inner.writes = contract_writes
+                .into_iter()
+                .chain(system_writes)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+        }
+    });
+
     for model in &models {
         contracts.remove(model.0.as_str());
     }
@@ -406,6 +421,31 @@ fn get_dojo_model_artifacts(
     Ok(models)
 }
 
+/// Returns the models written by the systems of the dojo contracts declared in the given module,
+/// by contract.
+fn get_dojo_system_writes(
+    db: &RootDatabase,
+    aux_data: &DojoAuxData,
+    module_id: ModuleId,
+) -> HashMap<String, Vec<String>> {
+    let module_name = module_id.full_path(db);
+
+    aux_data
+        .systems
+        .iter()
+        .map(|system| {
+            let writes = system
+                .dependencies
+                .iter()
+                .filter(|dependency| dependency.write)
+                .map(|dependency| dependency.name.clone())
+                .collect();
+
+            (format!("{module_name}::{}", system.name), writes)
+        })
+        .collect()
+}
+
 fn get_dojo_computed_values(
     db: &RootDatabase,
     module_id: &ModuleId,
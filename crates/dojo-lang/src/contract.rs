@@ -1,78 +1,155 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use cairo_lang_defs::patcher::{PatchBuilder, RewriteNode};
 use cairo_lang_defs::plugin::{
     DynGeneratedFileAuxData, PluginDiagnostic, PluginGeneratedFile, PluginResult,
 };
 use cairo_lang_diagnostics::Severity;
-use cairo_lang_syntax::attribute::structured::{
-    Attribute, AttributeArg, AttributeArgVariant, AttributeListStructurize,
-};
-use cairo_lang_syntax::node::ast::MaybeModuleBody;
-use cairo_lang_syntax::node::db::SyntaxGroup;
-use cairo_lang_syntax::node::{ast, ids, Terminal, TypedStablePtr, TypedSyntaxNode};
+use cairo_lang_filesystem::db::{init_files_group, AsFilesGroupMut, FilesDatabase, FilesGroup};
+use cairo_lang_filesystem::ids::{FileKind, FileLongId, VirtualFile};
+use cairo_lang_parser::db::{ParserDatabase, ParserGroup};
+use cairo_lang_syntax::attribute::structured::AttributeStructurize;
+use cairo_lang_syntax::node::ast::{ArgClause, Expr, MaybeModuleBody, OptionArgListParenthesized};
+use cairo_lang_syntax::node::db::{SyntaxDatabase, SyntaxGroup};
+use cairo_lang_syntax::node::helpers::QueryAttrs;
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::{ast, ids, SyntaxNode, Terminal, TypedStablePtr, TypedSyntaxNode};
 use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
+use cairo_lang_utils::Upcast;
 use dojo_types::system::Dependency;
+use smol_str::SmolStr;
+use starknet::core::utils::get_selector_from_name;
 
-use crate::plugin::{DojoAuxData, SystemAuxData, DOJO_CONTRACT_ATTR};
+use crate::inline_macros::extract_models;
+use crate::plugin::{DojoAuxData, SystemAuxData, DOJO_CONTRACT_ATTR, DOJO_MODEL_ATTR};
 
 const ALLOW_REF_SELF_ARG: &str = "allow_ref_self";
+const UPGRADEABLE_EVENT_VARIANT: &str = "UpgradeableEvent";
+const UPGRADEABLE_STORAGE_FIELD: &str = "upgradeable";
+const PAUSED_STORAGE_FIELD: &str = "paused";
+const FLAT_ATTR: &str = "flat";
+const CONTRACT_VERSION_NAME: &str = "version";
+const DEFAULT_CONTRACT_VERSION: &str = "0.0.0";
+const WORLD_FIELD_NAME: &str = "world_field";
+const DEFAULT_WORLD_FIELD: &str = "world_dispatcher";
+const EXPOSE_DEPS_NAME: &str = "expose_deps";
+const UPGRADEABLE_NAME: &str = "upgradeable";
+const WRITES_NAME: &str = "writes";
+const ROLES_NAME: &str = "roles";
+const PAUSABLE_NAME: &str = "pausable";
+const EVENT_PREFIX_NAME: &str = "event_prefix";
+const EXPOSE_STORAGE_NAME: &str = "expose_storage";
+const STORAGE_GETTER_PREFIX: &str = "get_";
+
+/// Version of the ABI of the contracts generated by the dojo::contract attribute, returned by their
+/// `dojo_version` entrypoint so that tools can recognize them. Bump it on any breaking change of
+/// the generated entrypoints.
+pub const DOJO_ABI_VERSION: u32 = 1;
+
+/// Attributes exposing the functions of an impl in the contract ABI, or marking it as internal.
+const IMPL_EXPOSURE_ATTRS: [&str; 3] = ["abi", "external", "generate_trait"];
+
+/// Identifiers which can't be used to name a contract, as they would clash with the items
+/// generated by the dojo::contract attribute.
+const RESERVED_NAMES: [&str; 5] = ["world", "self", "Storage", "Event", "ContractState"];
+
+/// Maximum edit distance between an unknown model and a declared one for the latter to be
+/// suggested.
+const MAX_MODEL_TYPO_DISTANCE: usize = 2;
+
+struct ContractParameters {
+    allow_ref_self: bool,
+    version: String,
+    world_field: String,
+    expose_deps: bool,
+    upgradeable: bool,
+    writes: Vec<String>,
+    roles: Vec<String>,
+    pausable: bool,
+    event_prefix: String,
+    expose_storage: bool,
+}
+
+impl Default for ContractParameters {
+    fn default() -> ContractParameters {
+        ContractParameters {
+            allow_ref_self: false,
+            version: DEFAULT_CONTRACT_VERSION.to_string(),
+            world_field: DEFAULT_WORLD_FIELD.to_string(),
+            expose_deps: false,
+            upgradeable: true,
+            writes: vec![],
+            roles: vec![],
+            pausable: false,
+            event_prefix: String::new(),
+            expose_storage: false,
+        }
+    }
+}
+
+/// The usage of the `self` and `world` parameters by a function of the contract.
+struct SelfAndWorldUsage {
+    /// Whether the `self: @ContractState` parameter has to be added.
+    add_self: bool,
+    /// The index of the `world` parameter to remove, as it is read from the contract storage.
+    world_idx: Option<usize>,
+}
 
 pub struct DojoContract {
     diagnostics: Vec<PluginDiagnostic>,
-    dependencies: HashMap<smol_str::SmolStr, Dependency>,
+    dependencies: HashMap<SmolStr, Dependency>,
     do_allow_ref_self: bool,
+    world_field: String,
+    is_upgradeable: bool,
+    is_pausable: bool,
+    is_storage_exposed: bool,
+    /// The fields declared by the user in the storage of the contract, along with their type, for
+    /// which getters are generated when the storage is exposed.
+    storage_fields: Vec<(ast::Member, String)>,
+    /// The name of the event variant of the upgradeable component, prefixed with the
+    /// `event_prefix` of the contract so that its upgrade events can be told apart.
+    upgradeable_event_variant: String,
+    /// The models declared in the file of the contract, the models it uses being checked against
+    /// them if any.
+    declared_models: HashSet<String>,
+    /// The names imported in the file of the contract, which may be models declared elsewhere.
+    imported_names: HashSet<String>,
 }
 
 impl DojoContract {
     pub fn from_module(db: &dyn SyntaxGroup, module_ast: ast::ItemModule) -> PluginResult {
         let name = module_ast.name(db).text(db);
 
-        let attrs = module_ast.attributes(db).structurize(db);
-        let dojo_contract_attr = attrs.iter().find(|attr| attr.id.as_str() == DOJO_CONTRACT_ATTR);
-        let do_allow_ref_self = extract_allow_ref_self(dojo_contract_attr, db).unwrap_or_default();
+        if let Some(diagnostic) = check_contract_name(db, &module_ast) {
+            return PluginResult {
+                code: None,
+                diagnostics: vec![diagnostic],
+                remove_original_item: true,
+            };
+        }
 
-        let mut system =
-            DojoContract { diagnostics: vec![], dependencies: HashMap::new(), do_allow_ref_self };
-        let mut has_event = false;
-        let mut has_storage = false;
+        let (mut system, parameters) = DojoContract::new(db, &module_ast);
 
         if let MaybeModuleBody::Some(body) = module_ast.body(db) {
-            let mut body_nodes: Vec<_> = body
-                .items(db)
-                .elements(db)
-                .iter()
-                .flat_map(|el| {
-                    if let ast::ModuleItem::Enum(enum_ast) = el {
-                        if enum_ast.name(db).text(db).to_string() == "Event" {
-                            has_event = true;
-                            return system.merge_event(db, enum_ast.clone());
-                        }
-                    } else if let ast::ModuleItem::Struct(struct_ast) = el {
-                        if struct_ast.name(db).text(db).to_string() == "Storage" {
-                            has_storage = true;
-                            return system.merge_storage(db, struct_ast.clone());
-                        }
-                    } else if let ast::ModuleItem::Impl(impl_ast) = el {
-                        // If an implementation is not targetting the ContractState,
-                        // the auto injection of self and world is not applied.
-                        let trait_path = impl_ast.trait_path(db).node.get_text(db);
-                        if trait_path.contains("<ContractState>") {
-                            return system.rewrite_impl(db, impl_ast.clone());
-                        }
-                    }
-
-                    vec![RewriteNode::Copied(el.as_syntax_node())]
-                })
-                .collect();
-
-            if !has_event {
-                body_nodes.append(&mut system.create_event())
-            }
-
-            if !has_storage {
-                body_nodes.append(&mut system.create_storage())
-            }
+            let body_nodes = system.rewrite_body(db, &module_ast, body, parameters.expose_deps);
+
+            // The upgradeable component is omitted for immutable contracts.
+            let (upgradeable_component, upgradeable_impl) = if parameters.upgradeable {
+                (
+                    format!(
+                        "component!(path: dojo::components::upgradeable::upgradeable, storage: \
+                         upgradeable, event: {});",
+                        system.upgradeable_event_variant
+                    ),
+                    "#[abi(embed_v0)]
+                    impl UpgradableImpl = \
+                     dojo::components::upgradeable::upgradeable::UpgradableImpl<ContractState>;"
+                        .to_string(),
+                )
+            } else {
+                (String::new(), String::new())
+            };
 
             let mut builder = PatchBuilder::new(db, &module_ast);
             builder.add_modified(RewriteNode::interpolate_patched(
@@ -84,10 +161,11 @@ impl DojoContract {
                     use dojo::world::IWorldDispatcherTrait;
                     use dojo::world::IWorldProvider;
                     use dojo::world::IDojoResourceProvider;
+                    use dojo::world::IContractVersionProvider;
+                    use dojo::world::IDojoVersionProvider;
                     
                    
-                    component!(path: dojo::components::upgradeable::upgradeable, storage: \
-                 upgradeable, event: UpgradeableEvent);
+                    $upgradeable_component$
 
                     #[abi(embed_v0)]
                     impl DojoResourceProviderImpl of IDojoResourceProvider<ContractState> {
@@ -96,22 +174,43 @@ impl DojoContract {
                         }
                     }
 
+                    #[abi(embed_v0)]
+                    impl ContractVersionProviderImpl of IContractVersionProvider<ContractState> {
+                        fn contract_version(self: @ContractState) -> ByteArray {
+                            \"$version$\"
+                        }
+                    }
+
+                    #[abi(embed_v0)]
+                    impl DojoVersionProviderImpl of IDojoVersionProvider<ContractState> {
+                        fn dojo_version(self: @ContractState) -> felt252 {
+                            $dojo_version$
+                        }
+                    }
+
                     #[abi(embed_v0)]
                     impl WorldProviderImpl of IWorldProvider<ContractState> {
                         fn world(self: @ContractState) -> IWorldDispatcher {
-                            self.world_dispatcher.read()
+                            self.$world_field$.read()
+                        }
+
+                        fn world_address(self: @ContractState) -> starknet::ContractAddress {
+                            self.$world_field$.read().contract_address
                         }
                     }
 
-                    #[abi(embed_v0)]
-                    impl UpgradableImpl = \
-                 dojo::components::upgradeable::upgradeable::UpgradableImpl<ContractState>;
+                    $upgradeable_impl$
 
                     $body$
                 }
                 ",
                 &UnorderedHashMap::from([
                     ("name".to_string(), RewriteNode::Text(name.to_string())),
+                    ("version".to_string(), RewriteNode::Text(parameters.version.clone())),
+                    ("dojo_version".to_string(), RewriteNode::Text(DOJO_ABI_VERSION.to_string())),
+                    ("world_field".to_string(), RewriteNode::Text(parameters.world_field)),
+                    ("upgradeable_component".to_string(), RewriteNode::Text(upgradeable_component)),
+                    ("upgradeable_impl".to_string(), RewriteNode::Text(upgradeable_impl)),
                     ("body".to_string(), RewriteNode::new_modified(body_nodes)),
                 ]),
             ));
@@ -127,6 +226,8 @@ impl DojoContract {
                         systems: vec![SystemAuxData {
                             name,
                             dependencies: system.dependencies.values().cloned().collect(),
+                            version: parameters.version,
+                            roles: parameters.roles,
                         }],
                         events: vec![],
                     })),
@@ -140,6 +241,158 @@ impl DojoContract {
         PluginResult::default()
     }
 
+    /// Runs all the checks of [`DojoContract::from_module`] on a contract module, without
+    /// generating the expanded contract, so that the diagnostics can be reported cheaply (by a
+    /// language server for example).
+    pub fn diagnostics_only(
+        db: &dyn SyntaxGroup,
+        module_ast: ast::ItemModule,
+    ) -> Vec<PluginDiagnostic> {
+        if let Some(diagnostic) = check_contract_name(db, &module_ast) {
+            return vec![diagnostic];
+        }
+
+        let (mut system, parameters) = DojoContract::new(db, &module_ast);
+
+        if let MaybeModuleBody::Some(body) = module_ast.body(db) {
+            system.rewrite_body(db, &module_ast, body, parameters.expose_deps);
+        }
+
+        system.diagnostics
+    }
+
+    fn new(db: &dyn SyntaxGroup, module_ast: &ast::ItemModule) -> (Self, ContractParameters) {
+        let mut diagnostics = vec![];
+        let parameters = get_parameters(db, module_ast, &mut diagnostics);
+        let (declared_models, imported_names) = get_file_models_and_imports(db, module_ast);
+
+        let mut system = DojoContract {
+            diagnostics,
+            dependencies: HashMap::new(),
+            do_allow_ref_self: parameters.allow_ref_self,
+            world_field: parameters.world_field.clone(),
+            is_upgradeable: parameters.upgradeable,
+            is_pausable: parameters.pausable,
+            is_storage_exposed: parameters.expose_storage,
+            storage_fields: vec![],
+            upgradeable_event_variant: format!(
+                "{}{}",
+                parameters.event_prefix, UPGRADEABLE_EVENT_VARIANT
+            ),
+            declared_models,
+            imported_names,
+        };
+
+        // the models declared as written are recorded along with the ones written by the systems
+        for model in &parameters.writes {
+            system.add_dependency(SmolStr::from(model.as_str()), true);
+        }
+
+        (system, parameters)
+    }
+
+    /// Rewrites the items of the contract module body, completed with the items generated when
+    /// they are not declared by the user.
+    fn rewrite_body(
+        &mut self,
+        db: &dyn SyntaxGroup,
+        module_ast: &ast::ItemModule,
+        body: ast::ModuleBody,
+        expose_deps: bool,
+    ) -> Vec<RewriteNode> {
+        let mut has_event = false;
+        let mut has_storage = false;
+        let mut has_contract_impl = false;
+
+        let mut body_nodes: Vec<_> = body
+            .items(db)
+            .elements(db)
+            .iter()
+            .flat_map(|el| {
+                if let ast::ModuleItem::Enum(enum_ast) = el {
+                    if enum_ast.name(db).text(db).to_string() == "Event" {
+                        has_event = true;
+                        return self.merge_event(db, enum_ast.clone());
+                    }
+                } else if let ast::ModuleItem::Struct(struct_ast) = el {
+                    if struct_ast.name(db).text(db).to_string() == "Storage" {
+                        has_storage = true;
+                        return self.merge_storage(db, struct_ast.clone());
+                    }
+                } else if let ast::ModuleItem::Impl(impl_ast) = el {
+                    self.check_embedded_generate_trait(db, impl_ast);
+
+                    // If an implementation is not targetting the ContractState,
+                    // the auto injection of self and world is not applied.
+                    let trait_path = impl_ast.trait_path(db).node.get_text(db);
+                    if trait_path.contains("<ContractState>") {
+                        has_contract_impl = true;
+                        self.check_impl_exposure(db, impl_ast);
+                        return self.rewrite_impl(db, impl_ast.clone());
+                    }
+
+                    if impl_ast.has_attr(db, "generate_trait") {
+                        self.check_generate_trait_impl(db, impl_ast);
+                    }
+                } else if let ast::ModuleItem::FreeFunction(fn_ast) = el {
+                    // Free functions taking a `world` parameter are rewritten like the
+                    // functions of the ContractState impls, to read `world` from the storage.
+                    let param_list = fn_ast.declaration(db).signature(db).parameters(db);
+                    if self.has_world_parameter(db, param_list) {
+                        return self.rewrite_function(db, fn_ast.clone());
+                    }
+                }
+
+                vec![RewriteNode::Copied(el.as_syntax_node())]
+            })
+            .collect();
+
+        // Without any impl for the ContractState, nor storage or events, the contract is
+        // only made of the generated boilerplate, which is most likely a mistake.
+        if !has_contract_impl && !has_storage && !has_event {
+            self.diagnostics.push(PluginDiagnostic {
+                stable_ptr: module_ast.name(db).stable_ptr().untyped(),
+                message: format!(
+                    "The contract '{}' has no impl for ContractState, no system is exposed.",
+                    module_ast.name(db).text(db)
+                ),
+                severity: Severity::Warning,
+            });
+        }
+
+        if !has_event {
+            body_nodes.append(&mut self.create_event())
+        }
+
+        if !has_storage {
+            body_nodes.append(&mut self.create_storage())
+        }
+
+        if expose_deps {
+            body_nodes.append(&mut self.create_dependencies_provider())
+        }
+
+        if self.is_pausable {
+            body_nodes.append(&mut self.create_pausable())
+        }
+
+        if self.is_storage_exposed {
+            body_nodes.append(&mut self.create_storage_getters(db, &body))
+        }
+
+        body_nodes
+    }
+
+    /// Merges the `Event` enum declared by the user with the events injected by dojo::contract.
+    ///
+    /// The variants are kept as declared, along with their attributes. The supported forms are:
+    /// - `Name: Name`, an event of the contract itself,
+    /// - `Name: path::to::component::Event`, the events of a component, kept nested,
+    /// - `#[flat] Name: path::to::component::Event`, the events of a component, flattened into the
+    ///   events of the contract.
+    ///
+    /// The attributes of the enum are kept too, its derives being completed with the ones
+    /// required by the events of a contract.
     pub fn merge_event(
         &mut self,
         db: &dyn SyntaxGroup,
@@ -147,35 +400,271 @@ impl DojoContract {
     ) -> Vec<RewriteNode> {
         let mut rewrite_nodes = vec![];
 
+        let mut derives = vec!["Drop".to_string(), "starknet::Event".to_string()];
+        let mut attributes = vec![];
+
+        for attr in enum_ast.attributes(db).elements(db) {
+            match attr.attr(db).as_syntax_node().get_text_without_trivia(db).as_str() {
+                "event" => {}
+                "derive" => {
+                    for arg in attr.structurize(db).args {
+                        let derive = arg.arg.as_syntax_node().get_text_without_trivia(db);
+                        if !derives.contains(&derive) {
+                            derives.push(derive);
+                        }
+                    }
+                }
+                _ => attributes.push(attr.as_syntax_node().get_text_without_trivia(db)),
+            }
+        }
+
         let elements = enum_ast.variants(db).elements(db);
 
-        let variants = elements.iter().map(|e| e.as_syntax_node().get_text(db)).collect::<Vec<_>>();
+        let mut variant_names = HashSet::new();
+        let mut variants = vec![];
+
+        for variant in elements.iter() {
+            let variant_name = variant.name(db);
+            let name = variant_name.text(db);
+
+            if self.is_upgradeable && name == self.upgradeable_event_variant {
+                self.diagnostics.push(PluginDiagnostic {
+                    stable_ptr: variant_name.stable_ptr().untyped(),
+                    message: format!(
+                        "The '{}' variant is reserved, it is injected by dojo::contract.",
+                        self.upgradeable_event_variant
+                    ),
+                    severity: Severity::Error,
+                });
+            } else if !variant_names.insert(name.clone()) {
+                self.diagnostics.push(PluginDiagnostic {
+                    stable_ptr: variant_name.stable_ptr().untyped(),
+                    message: format!("The '{}' variant is declared more than once.", name),
+                    severity: Severity::Error,
+                });
+            } else if variant.attributes(db).has_attr(db, FLAT_ATTR)
+                && matches!(variant.type_clause(db), ast::OptionTypeClause::Empty(_))
+            {
+                self.diagnostics.push(PluginDiagnostic {
+                    stable_ptr: variant_name.stable_ptr().untyped(),
+                    message: format!(
+                        "The flat variant '{}' must hold an event enum, such as the events of a \
+                         component.",
+                        name
+                    ),
+                    severity: Severity::Error,
+                });
+            } else {
+                variants.push(variant.as_syntax_node().get_text(db));
+            }
+        }
+
         let variants = variants.join(",\n");
 
         rewrite_nodes.push(RewriteNode::interpolate_patched(
             "
+            $attributes$
             #[event]
-            #[derive(Drop, starknet::Event)]
+            #[derive($derives$)]
             enum Event {
-                UpgradeableEvent: dojo::components::upgradeable::upgradeable::Event,
+                $upgradeable_variant$
                 $variants$
             }
             ",
-            &UnorderedHashMap::from([("variants".to_string(), RewriteNode::Text(variants))]),
+            &UnorderedHashMap::from([
+                ("attributes".to_string(), RewriteNode::Text(attributes.join("\n"))),
+                ("derives".to_string(), RewriteNode::Text(derives.join(", "))),
+                ("upgradeable_variant".to_string(), RewriteNode::Text(self.upgradeable_variant())),
+                ("variants".to_string(), RewriteNode::Text(variants)),
+            ]),
         ));
         rewrite_nodes
     }
 
     pub fn create_event(&mut self) -> Vec<RewriteNode> {
-        vec![RewriteNode::Text(
+        vec![RewriteNode::interpolate_patched(
             "
             #[event]
             #[derive(Drop, starknet::Event)]
             enum Event {
-                UpgradeableEvent: dojo::components::upgradeable::upgradeable::Event,
+                $upgradeable_variant$
             }
+            ",
+            &UnorderedHashMap::from([(
+                "upgradeable_variant".to_string(),
+                RewriteNode::Text(self.upgradeable_variant()),
+            )]),
+        )]
+    }
+
+    /// Returns the event variant of the upgradeable component, if the contract is upgradeable.
+    fn upgradeable_variant(&self) -> String {
+        if self.is_upgradeable {
+            format!(
+                "{}: dojo::components::upgradeable::upgradeable::Event,",
+                self.upgradeable_event_variant
+            )
+        } else {
+            String::new()
+        }
+    }
+
+    /// Returns the storage member of the upgradeable component, if the contract is upgradeable.
+    fn upgradeable_storage(&self) -> String {
+        if self.is_upgradeable {
+            "#[substorage(v0)]
+                upgradeable: dojo::components::upgradeable::upgradeable::Storage,"
+                .to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    /// Returns the storage members injected by dojo::contract, besides the world dispatcher.
+    fn injected_storage(&self) -> String {
+        let mut storage = self.upgradeable_storage();
+        if self.is_pausable {
+            storage.push_str(&format!("\n                {}: bool,", PAUSED_STORAGE_FIELD));
+        }
+        storage
+    }
+
+    /// Checks if a storage field is injected by dojo::contract.
+    fn is_injected_field(&self, name: &str) -> bool {
+        name == self.world_field
+            || (self.is_upgradeable && name == UPGRADEABLE_STORAGE_FIELD)
+            || (self.is_pausable && name == PAUSED_STORAGE_FIELD)
+    }
+
+    /// Generates the `pause` and `unpause` entrypoints of a pausable contract, which can only be
+    /// called by the world, and the `when_not_paused` helper asserting in the systems that the
+    /// contract is not paused.
+    pub fn create_pausable(&mut self) -> Vec<RewriteNode> {
+        vec![RewriteNode::interpolate_patched(
             "
-            .to_string(),
+            #[abi(embed_v0)]
+            impl PausableImpl of dojo::world::IPausable<ContractState> {
+                fn pause(ref self: ContractState) {
+                    self.assert_caller_is_world();
+                    self.$paused$.write(true);
+                }
+
+                fn unpause(ref self: ContractState) {
+                    self.assert_caller_is_world();
+                    self.$paused$.write(false);
+                }
+
+                fn is_paused(self: @ContractState) -> bool {
+                    self.$paused$.read()
+                }
+            }
+
+            #[generate_trait]
+            impl PausableInternalImpl of PausableInternalTrait {
+                fn when_not_paused(self: @ContractState) {
+                    assert(!self.$paused$.read(), 'contract is paused');
+                }
+
+                fn assert_caller_is_world(self: @ContractState) {
+                    let world = self.$world_field$.read();
+                    assert(
+                        starknet::get_caller_address() == world.contract_address,
+                        'must be called by world'
+                    );
+                }
+            }
+            ",
+            &UnorderedHashMap::from([
+                ("paused".to_string(), RewriteNode::Text(PAUSED_STORAGE_FIELD.to_string())),
+                ("world_field".to_string(), RewriteNode::Text(self.world_field.clone())),
+            ]),
+        )]
+    }
+
+    /// Generates a `get_<field>` view for each field declared by the user in the contract storage,
+    /// the injected fields being left out.
+    ///
+    /// The getters colliding with a function of the contract are reported, and not generated.
+    pub fn create_storage_getters(
+        &mut self,
+        db: &dyn SyntaxGroup,
+        body: &ast::ModuleBody,
+    ) -> Vec<RewriteNode> {
+        let functions = contract_function_names(db, body);
+        let mut declarations = vec![];
+        let mut getters = vec![];
+
+        for (member, ty) in std::mem::take(&mut self.storage_fields) {
+            let name = member.name(db).text(db);
+            let getter = format!("{}{}", STORAGE_GETTER_PREFIX, name);
+
+            if functions.contains(&getter) {
+                self.diagnostics.push(PluginDiagnostic {
+                    stable_ptr: member.name(db).stable_ptr().untyped(),
+                    message: format!(
+                        "The getter '{}' of the storage field '{}' collides with a function of \
+                         the contract. Rename the function or the field.",
+                        getter, name
+                    ),
+                    severity: Severity::Error,
+                });
+                continue;
+            }
+
+            declarations.push(format!("fn {}(self: @TContractState) -> {};", getter, ty));
+            getters.push(format!(
+                "fn {}(self: @ContractState) -> {} {{
+                    self.{}.read()
+                }}",
+                getter, ty, name
+            ));
+        }
+
+        if getters.is_empty() {
+            return vec![];
+        }
+
+        vec![RewriteNode::interpolate_patched(
+            "
+            #[starknet::interface]
+            trait IStorageGetters<TContractState> {
+                $declarations$
+            }
+
+            #[abi(embed_v0)]
+            impl StorageGettersImpl of IStorageGetters<ContractState> {
+                $getters$
+            }
+            ",
+            &UnorderedHashMap::from([
+                ("declarations".to_string(), RewriteNode::Text(declarations.join("\n"))),
+                ("getters".to_string(), RewriteNode::Text(getters.join("\n\n"))),
+            ]),
+        )]
+    }
+
+    /// Generates a getter returning the selectors of the models the contract depends on, computed
+    /// from the dependencies collected while rewriting the contract functions.
+    pub fn create_dependencies_provider(&mut self) -> Vec<RewriteNode> {
+        let mut names = self.dependencies.keys().collect::<Vec<_>>();
+        names.sort();
+
+        let selectors = names
+            .iter()
+            .map(|name| get_selector_from_name(name).unwrap().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        vec![RewriteNode::interpolate_patched(
+            "
+            #[abi(embed_v0)]
+            impl DependenciesProviderImpl of dojo::world::IDependenciesProvider<ContractState> {
+                fn dependencies(self: @ContractState) -> Array<felt252> {
+                    array![$selectors$]
+                }
+            }
+            ",
+            &UnorderedHashMap::from([("selectors".to_string(), RewriteNode::Text(selectors))]),
         )]
     }
 
@@ -186,8 +675,48 @@ impl DojoContract {
     ) -> Vec<RewriteNode> {
         let mut rewrite_nodes = vec![];
 
+        // the struct is rewritten as the contract storage whatever its attributes, but without
+        // #[storage] it reads as a plain struct, which is most likely a mistake.
+        if !struct_ast.has_attr(db, "storage") {
+            self.diagnostics.push(PluginDiagnostic {
+                stable_ptr: struct_ast.name(db).stable_ptr().untyped(),
+                message: "The struct 'Storage' of a dojo::contract is used as the contract \
+                          storage but is missing the #[storage] attribute. Add #[storage] to the \
+                          struct, or rename it if it's not meant to be the storage."
+                    .to_string(),
+                severity: Severity::Warning,
+            });
+        }
+
         let elements = struct_ast.members(db).elements(db);
 
+        // the fields injected in the storage can't be declared by the user too
+        for member in &elements {
+            let name = member.name(db).text(db);
+            if self.is_injected_field(&name) {
+                self.diagnostics.push(PluginDiagnostic {
+                    stable_ptr: member.name(db).stable_ptr().untyped(),
+                    message: format!(
+                        "The storage field '{}' is injected by dojo::contract. Rename this field.",
+                        name
+                    ),
+                    severity: Severity::Error,
+                });
+            }
+        }
+
+        // the components storages and the maps can't be read as a whole
+        self.storage_fields = elements
+            .iter()
+            .filter(|member| !self.is_injected_field(&member.name(db).text(db)))
+            .filter(|member| !member.attributes(db).has_attr(db, "substorage"))
+            .map(|member| {
+                let ty = member.type_clause(db).ty(db).as_syntax_node().get_text_without_trivia(db);
+                (member.clone(), ty)
+            })
+            .filter(|(_, ty)| !ty.starts_with("LegacyMap"))
+            .collect();
+
         let members = elements.iter().map(|e| e.as_syntax_node().get_text(db)).collect::<Vec<_>>();
         let members = members.join(",\n");
 
@@ -195,28 +724,33 @@ impl DojoContract {
             "
             #[storage]
             struct Storage {
-                world_dispatcher: IWorldDispatcher,
-                #[substorage(v0)]
-                upgradeable: dojo::components::upgradeable::upgradeable::Storage,
+                $world_field$: IWorldDispatcher,
+                $injected_storage$
                 $members$
             }
             ",
-            &UnorderedHashMap::from([("members".to_string(), RewriteNode::Text(members))]),
+            &UnorderedHashMap::from([
+                ("world_field".to_string(), RewriteNode::Text(self.world_field.clone())),
+                ("injected_storage".to_string(), RewriteNode::Text(self.injected_storage())),
+                ("members".to_string(), RewriteNode::Text(members)),
+            ]),
         ));
         rewrite_nodes
     }
 
     pub fn create_storage(&mut self) -> Vec<RewriteNode> {
-        vec![RewriteNode::Text(
+        vec![RewriteNode::interpolate_patched(
             "
             #[storage]
             struct Storage {
-                world_dispatcher: IWorldDispatcher,
-                #[substorage(v0)]
-                upgradeable: dojo::components::upgradeable::upgradeable::Storage,
+                $world_field$: IWorldDispatcher,
+                $injected_storage$
             }
-            "
-            .to_string(),
+            ",
+            &UnorderedHashMap::from([
+                ("world_field".to_string(), RewriteNode::Text(self.world_field.clone())),
+                ("injected_storage".to_string(), RewriteNode::Text(self.injected_storage())),
+            ]),
         )]
     }
 
@@ -234,59 +768,141 @@ impl DojoContract {
         (name, modifiers, param_type)
     }
 
-    /// Check if the function has a self parameter.
+    /// Checks the usage of the `self` and `world` parameters of a function of the contract.
     ///
-    /// Returns
-    ///  * a boolean indicating if `self` has to be added,
-    //   * a boolean indicating if there is a `ref self` parameter.
-    pub fn check_self_parameter(
+    /// In an impl targetting the ContractState, `self` must be the first parameter, as
+    /// `self: @ContractState` (or `ref self: ContractState` if allowed), and `world` must be
+    /// the first parameter (self excluded), as it is read from the contract storage.
+    ///
+    /// In a `#[generate_trait]` impl, the functions are not rewritten, so a `world` parameter along
+    /// with `self` is reported, as `world` is most likely expected to be read from the storage.
+    ///
+    /// At most one diagnostic is reported per function, a `self` misuse taking precedence over a
+    /// `world` one.
+    fn check_self_and_world(
         &mut self,
         db: &dyn SyntaxGroup,
         param_list: ast::ParamList,
-    ) -> (bool, bool) {
-        let mut add_self = true;
-        let mut has_ref_self = false;
-        if !param_list.elements(db).is_empty() {
-            let (param_name, param_modifiers, param_type) =
-                self.get_parameter_info(db, param_list.elements(db)[0].clone());
-
-            if param_name.eq(&"self".to_string()) {
-                if param_modifiers.contains(&"ref".to_string())
-                    && param_type.eq(&"ContractState".to_string())
-                {
-                    has_ref_self = true;
-                    add_self = false;
-                }
+        diagnostic_item: ids::SyntaxStablePtrId,
+        in_generate_trait: bool,
+    ) -> SelfAndWorldUsage {
+        let params = param_list.elements(db);
+        let infos = params
+            .iter()
+            .map(|param| self.get_parameter_info(db, param.clone()))
+            .collect::<Vec<_>>();
 
-                if param_type.eq(&"@ContractState".to_string()) {
-                    add_self = false;
+        let self_idx = infos.iter().position(|(name, _, _)| name == "self");
+        let world_idxs = infos
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, _, param_type))| param_type == "IWorldDispatcher")
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+
+        if in_generate_trait {
+            if self_idx.is_some() && !world_idxs.is_empty() {
+                self.diagnostics.push(PluginDiagnostic {
+                    stable_ptr: params[world_idxs[0]].stable_ptr().untyped(),
+                    message: format!(
+                        "The IWorldDispatcher parameter is not injected in #[generate_trait] \
+                         impls. Remove it and use 'self.{}.read()' instead.",
+                        self.world_field
+                    ),
+                    severity: Severity::Warning,
+                });
+            }
+
+            return SelfAndWorldUsage { add_self: false, world_idx: None };
+        }
+
+        let (add_self, self_error) = match self_idx {
+            None => (true, None),
+            Some(0) => match (infos[0].1.as_str(), infos[0].2.as_str()) {
+                ("", "@ContractState") => (false, None),
+                ("ref", "ContractState") if self.do_allow_ref_self => (false, None),
+                ("ref", "ContractState") => (
+                    false,
+                    Some((
+                        diagnostic_item,
+                        "Functions of dojo::contract cannot have 'ref self' parameter. Use 'self: \
+                         @ContractState', or allow it with #[dojo::contract(allow_ref_self)]."
+                            .to_string(),
+                    )),
+                ),
+                _ => (
+                    false,
+                    Some((
+                        params[0].stable_ptr().untyped(),
+                        "The 'self' parameter must be declared as 'self: @ContractState'."
+                            .to_string(),
+                    )),
+                ),
+            },
+            Some(idx) => (
+                false,
+                Some((
+                    params[idx].stable_ptr().untyped(),
+                    "The 'self' parameter must be the first parameter of the function.".to_string(),
+                )),
+            ),
+        };
+
+        let world_error = match world_idxs[..] {
+            [] => None,
+            [idx] if infos[idx].1.is_empty() => {
+                let expected_idx = if add_self { 0 } else { 1 };
+
+                if idx != expected_idx {
+                    Some((
+                        params[idx].stable_ptr().untyped(),
+                        "The IWorldDispatcher parameter must be the first parameter of the \
+                         function (self excluded)."
+                            .to_string(),
+                    ))
+                } else if infos[idx].0 != "world" {
+                    Some((
+                        params[idx].stable_ptr().untyped(),
+                        "The IWorldDispatcher parameter must be named 'world'.".to_string(),
+                    ))
+                } else {
+                    None
                 }
             }
+            [_] => None,
+            _ => Some((
+                diagnostic_item,
+                "Only one parameter of type IWorldDispatcher is allowed.".to_string(),
+            )),
+        };
+
+        let world_idx = match world_idxs[..] {
+            [idx] if world_error.is_none() && infos[idx].1.is_empty() => Some(idx),
+            _ => None,
         };
 
-        (add_self, has_ref_self)
+        if let Some((stable_ptr, message)) = self_error.or(world_error) {
+            self.diagnostics.push(PluginDiagnostic {
+                stable_ptr,
+                message,
+                severity: Severity::Error,
+            });
+        }
+
+        SelfAndWorldUsage { add_self, world_idx }
     }
 
-    /// Check if the function has multiple IWorldDispatcher parameters.
-    ///
-    /// Returns
-    ///  * a boolean indicating if the function has multiple world dispatchers.
-    pub fn check_world_dispatcher(
+    /// Check if the function has a `world` parameter of type IWorldDispatcher.
+    pub fn has_world_parameter(
         &mut self,
         db: &dyn SyntaxGroup,
         param_list: ast::ParamList,
     ) -> bool {
-        let mut count = 0;
-
-        param_list.elements(db).iter().for_each(|param| {
-            let (_, _, param_type) = self.get_parameter_info(db, param.clone());
-
-            if param_type.eq(&"IWorldDispatcher".to_string()) {
-                count += 1;
-            }
-        });
+        param_list.elements(db).iter().any(|param| {
+            let (name, modifiers, param_type) = self.get_parameter_info(db, param.clone());
 
-        count > 1
+            name.eq("world") && modifiers.is_empty() && param_type.eq("IWorldDispatcher")
+        })
     }
 
     /// Rewrites parameter list by:
@@ -294,10 +910,7 @@ impl DojoContract {
     ///  * removing `world` if present as first parameter (self excluded), as it will be read from
     ///    the first function statement.
     ///
-    /// Reports an error in case of:
-    ///  * `ref self`, as systems are supposed to be 100% stateless,
-    ///  * multiple IWorldDispatcher parameters.
-    ///  * the `IWorldDispatcher` is not the first parameter (self excluded) and named 'world'.
+    /// The misuses of `self` and `world` are reported by `check_self_and_world`.
     ///
     /// Returns
     ///  * the list of parameters in a String
@@ -309,84 +922,59 @@ impl DojoContract {
         param_list: ast::ParamList,
         diagnostic_item: ids::SyntaxStablePtrId,
     ) -> (String, bool, bool) {
-        let (add_self, has_ref_self) = self.check_self_parameter(db, param_list.clone());
-        let has_multiple_world_dispatchers = self.check_world_dispatcher(db, param_list.clone());
-
-        let mut world_removed = false;
+        let usage = self.check_self_and_world(db, param_list.clone(), diagnostic_item, false);
 
         let mut params = param_list
             .elements(db)
             .iter()
             .enumerate()
-            .filter_map(|(idx, param)| {
-                let (name, modifiers, param_type) = self.get_parameter_info(db, param.clone());
-
-                if param_type.eq(&"IWorldDispatcher".to_string())
-                    && modifiers.eq(&"".to_string())
-                    && !has_multiple_world_dispatchers
-                {
-                    let has_good_pos = (add_self && idx == 0) || (!add_self && idx == 1);
-                    let has_good_name = name.eq(&"world".to_string());
-
-                    if has_good_pos && has_good_name {
-                        world_removed = true;
-                        None
-                    } else {
-                        if !has_good_pos {
-                            self.diagnostics.push(PluginDiagnostic {
-                                stable_ptr: param.stable_ptr().untyped(),
-                                message: "The IWorldDispatcher parameter must be the first \
-                                          parameter of the function (self excluded)."
-                                    .to_string(),
-                                severity: Severity::Error,
-                            });
-                        }
-
-                        if !has_good_name {
-                            self.diagnostics.push(PluginDiagnostic {
-                                stable_ptr: param.stable_ptr().untyped(),
-                                message: "The IWorldDispatcher parameter must be named 'world'."
-                                    .to_string(),
-                                severity: Severity::Error,
-                            });
-                        }
-                        Some(param.as_syntax_node().get_text(db))
-                    }
-                } else {
-                    Some(param.as_syntax_node().get_text(db))
-                }
-            })
+            .filter(|(idx, _)| usage.world_idx != Some(*idx))
+            .map(|(_, param)| param.as_syntax_node().get_text(db))
             .collect::<Vec<_>>();
 
-        if has_multiple_world_dispatchers {
-            self.diagnostics.push(PluginDiagnostic {
-                stable_ptr: diagnostic_item,
-                message: "Only one parameter of type IWorldDispatcher is allowed.".to_string(),
-                severity: Severity::Error,
-            });
+        if usage.add_self {
+            params.insert(0, "self: @ContractState".to_string());
         }
 
-        if has_ref_self && !self.do_allow_ref_self {
-            self.diagnostics.push(PluginDiagnostic {
-                stable_ptr: diagnostic_item,
-                message: "Functions of dojo::contract cannot have 'ref self' parameter."
-                    .to_string(),
-                severity: Severity::Error,
-            });
-        }
+        (params.join(", "), usage.add_self, usage.world_idx.is_some())
+    }
 
-        if add_self {
-            params.insert(0, "self: @ContractState".to_string());
-        }
+    /// Reports the top-level `world` bindings of the function statements, as they would shadow
+    /// the `world` variable injected at first statement.
+    fn check_world_shadowing(&mut self, db: &dyn SyntaxGroup, statement_list: ast::StatementList) {
+        for statement in statement_list.elements(db) {
+            let ast::Statement::Let(let_statement) = statement else {
+                continue;
+            };
+
+            // a plain `world` binding is parsed as a path, `mut world` as an identifier
+            let pattern = let_statement.pattern(db);
+            let binds_world = match &pattern {
+                ast::Pattern::Identifier(identifier) => identifier.name(db).text(db) == "world",
+                ast::Pattern::Path(path) => {
+                    path.as_syntax_node().get_text_without_trivia(db) == "world"
+                }
+                _ => false,
+            };
 
-        (params.join(", "), add_self, world_removed)
+            if binds_world {
+                self.diagnostics.push(PluginDiagnostic {
+                    stable_ptr: pattern.stable_ptr().untyped(),
+                    message: "The 'world' variable is already read from the IWorldDispatcher \
+                              parameter. Rename this local variable."
+                        .to_string(),
+                    severity: Severity::Error,
+                });
+            }
+        }
     }
 
-    /// Rewrites function statements by adding the reading of `world` at first statement.
+    /// Rewrites function statements by adding the reading of `world` from the `world_field`
+    /// storage field at first statement.
     pub fn rewrite_statements(
-        &mut self,
         db: &dyn SyntaxGroup,
         statement_list: ast::StatementList,
+        world_field: &str,
     ) -> String {
         let mut statements = statement_list
             .elements(db)
@@ -394,20 +982,25 @@ impl DojoContract {
             .map(|e| e.as_syntax_node().get_text(db))
             .collect::<Vec<_>>();
 
-        statements.insert(0, "let world = self.world_dispatcher.read();\n".to_string());
+        statements.insert(0, format!("let world = self.{}.read();\n", world_field));
         statements.join("")
     }
 
     /// Rewrites function declaration by:
     ///  * adding `self` parameter if missing,
     ///  * removing `world` if present as first parameter (self excluded),
-    ///  * adding `let world = self.world_dispatcher.read();` statement at the beginning of the
+    ///  * adding `let world = self.<world_field>.read();` statement at the beginning of the
     ///    function to restore the removed `world` parameter.
+    ///
+    /// Only the parameters and the statements are modified, the doc comments and the attributes
+    /// of the function being kept as written, as the ABI docs are extracted from them.
     pub fn rewrite_function(
         &mut self,
         db: &dyn SyntaxGroup,
         fn_ast: ast::FunctionWithBody,
     ) -> Vec<RewriteNode> {
+        self.collect_dependencies(db, fn_ast.body(db).as_syntax_node());
+
         let mut rewritten_fn = RewriteNode::from_ast(&fn_ast);
 
         let (params_str, self_added, world_removed) = self.rewrite_parameters(
@@ -425,17 +1018,187 @@ impl DojoContract {
         }
 
         if world_removed {
+            self.check_world_shadowing(db, fn_ast.body(db).statements(db));
+
             let rewritten_statements = rewritten_fn
                 .modify_child(db, ast::FunctionWithBody::INDEX_BODY)
                 .modify_child(db, ast::ExprBlock::INDEX_STATEMENTS);
 
-            rewritten_statements
-                .set_str(self.rewrite_statements(db, fn_ast.body(db).statements(db)));
+            rewritten_statements.set_str(Self::rewrite_statements(
+                db,
+                fn_ast.body(db).statements(db),
+                &self.world_field,
+            ));
         }
 
         vec![rewritten_fn]
     }
 
+    /// Collects the models read by `get!` and written by `set!` or `delete!` in the provided
+    /// syntax node and its children.
+    fn collect_dependencies(&mut self, db: &dyn SyntaxGroup, node: SyntaxNode) {
+        if node.kind(db) == SyntaxKind::ExprInlineMacro {
+            let macro_ast = ast::ExprInlineMacro::from_syntax_node(db, node.clone());
+            let macro_name = macro_ast.path(db).as_syntax_node().get_text_without_trivia(db);
+
+            if let ast::WrappedArgList::ParenthesizedArgList(arg_list) = macro_ast.arguments(db) {
+                let args = arg_list.arguments(db).elements(db);
+
+                match (macro_name.as_str(), &args[..]) {
+                    ("get", [_, _, models]) => {
+                        if let ast::ArgClause::Unnamed(models) = models.arg_clause(db) {
+                            let models = models.value(db);
+                            for model in extract_models(db, &models).unwrap_or_default() {
+                                self.check_model_declared(&model, models.stable_ptr().untyped());
+                                self.add_dependency(model, false);
+                            }
+                        }
+                    }
+                    ("set" | "delete", [_, models]) => {
+                        if let ast::ArgClause::Unnamed(models) = models.arg_clause(db) {
+                            let models = models.value(db);
+                            for model in extract_model_ctors(db, &models) {
+                                self.check_model_declared(&model, models.stable_ptr().untyped());
+                                self.add_dependency(model, true);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for child in db.get_children(node).iter() {
+            self.collect_dependencies(db, child.clone());
+        }
+    }
+
+    /// Warns when a model used by the contract is neither declared nor imported in its file,
+    /// suggesting the closest declared model as it is likely a typo.
+    ///
+    /// The models of the other files are not known by the plugin, hence the check is only done
+    /// when some models are declared along with the contract.
+    fn check_model_declared(&mut self, model: &str, stable_ptr: ids::SyntaxStablePtrId) {
+        if self.declared_models.is_empty()
+            || self.declared_models.contains(model)
+            || self.imported_names.contains(model)
+        {
+            return;
+        }
+
+        let suggestion = self
+            .declared_models
+            .iter()
+            .map(|declared| (edit_distance(model, declared), declared))
+            .filter(|(distance, _)| *distance <= MAX_MODEL_TYPO_DISTANCE)
+            .min();
+
+        let message = match suggestion {
+            Some((_, declared)) => format!(
+                "The model '{}' is not declared in this file, did you mean '{}'?",
+                model, declared
+            ),
+            None => format!("The model '{}' is not declared in this file.", model),
+        };
+
+        self.diagnostics.push(PluginDiagnostic {
+            stable_ptr,
+            message,
+            severity: Severity::Warning,
+        });
+    }
+
+    /// Records a read or a write access to a model.
+    fn add_dependency(&mut self, model: SmolStr, write: bool) {
+        let dependency = self.dependencies.entry(model.clone()).or_insert(Dependency {
+            name: model.to_string(),
+            read: false,
+            write: false,
+        });
+
+        if write {
+            dependency.write = true;
+        } else {
+            dependency.read = true;
+        }
+    }
+
+    /// Warns when an impl of an interface targetting the ContractState is neither exposed in the
+    /// contract ABI nor generating its trait, as its functions are then not callable.
+    ///
+    /// Only traits named like interfaces (`IActions`) are considered as intended entrypoints, impls
+    /// of other traits being considered as internal helpers.
+    fn check_impl_exposure(&mut self, db: &dyn SyntaxGroup, impl_ast: &ast::ItemImpl) {
+        if IMPL_EXPOSURE_ATTRS.iter().any(|attr| impl_ast.has_attr(db, attr)) {
+            return;
+        }
+
+        let trait_name = match impl_ast.trait_path(db).elements(db).last() {
+            Some(ast::PathSegment::Simple(segment)) => segment.ident(db).text(db),
+            Some(ast::PathSegment::WithGenericArgs(segment)) => segment.ident(db).text(db),
+            None => return,
+        };
+
+        if is_interface_name(&trait_name) {
+            self.diagnostics.push(PluginDiagnostic {
+                stable_ptr: impl_ast.name(db).stable_ptr().untyped(),
+                message: format!(
+                    "The impl '{}' of '{}' is not exposed in the contract ABI, its functions \
+                     can't be called. Add #[abi(embed_v0)] to expose them, or #[generate_trait] \
+                     if the impl is internal.",
+                    impl_ast.name(db).text(db),
+                    trait_name
+                ),
+                severity: Severity::Warning,
+            });
+        }
+    }
+
+    /// Reports the impls both generating their trait and embedded in the contract ABI, as the
+    /// generated traits are not interfaces and their impls can't be embedded.
+    fn check_embedded_generate_trait(&mut self, db: &dyn SyntaxGroup, impl_ast: &ast::ItemImpl) {
+        if !impl_ast.has_attr(db, "generate_trait") {
+            return;
+        }
+
+        let is_embedded = impl_ast.attributes(db).query_attr(db, "abi").into_iter().any(|attr| {
+            attr.structurize(db)
+                .args
+                .iter()
+                .any(|arg| arg.arg.as_syntax_node().get_text_without_trivia(db) == "embed_v0")
+        });
+
+        if is_embedded {
+            self.diagnostics.push(PluginDiagnostic {
+                stable_ptr: impl_ast.name(db).stable_ptr().untyped(),
+                message: format!(
+                    "The impl '{}' can't have both #[generate_trait] and #[abi(embed_v0)], as the \
+                     impls of generated traits can't be embedded. Remove #[abi(embed_v0)] if the \
+                     impl is internal, or #[generate_trait] to implement an interface.",
+                    impl_ast.name(db).text(db)
+                ),
+                severity: Severity::Error,
+            });
+        }
+    }
+
+    /// Checks the usage of `self` and `world` by the functions of a `#[generate_trait]` impl,
+    /// which are not rewritten.
+    fn check_generate_trait_impl(&mut self, db: &dyn SyntaxGroup, impl_ast: &ast::ItemImpl) {
+        if let ast::MaybeImplBody::Some(body) = impl_ast.body(db) {
+            for item in body.items(db).elements(db) {
+                if let ast::ImplItem::Function(fn_ast) = item {
+                    self.check_self_and_world(
+                        db,
+                        fn_ast.declaration(db).signature(db).parameters(db),
+                        fn_ast.stable_ptr().untyped(),
+                        true,
+                    );
+                }
+            }
+        }
+    }
+
     /// Rewrites all the functions of a Impl block.
     fn rewrite_impl(&mut self, db: &dyn SyntaxGroup, impl_ast: ast::ItemImpl) -> Vec<RewriteNode> {
         if let ast::MaybeImplBody::Some(body) = impl_ast.body(db) {
@@ -475,25 +1238,589 @@ impl DojoContract {
     }
 }
 
-/// Extract the allow_ref_self attribute.
-pub(crate) fn extract_allow_ref_self(
-    allow_ref_self_attr: Option<&Attribute>,
-    db: &dyn SyntaxGroup,
-) -> Option<bool> {
-    let Some(attr) = allow_ref_self_attr else {
-        return None;
+/// A minimal database, only able to parse the code of the contracts to expand.
+#[salsa::database(ParserDatabase, SyntaxDatabase, FilesDatabase)]
+struct ContractExpansionDatabase {
+    storage: salsa::Storage<ContractExpansionDatabase>,
+}
+impl salsa::Database for ContractExpansionDatabase {}
+impl Default for ContractExpansionDatabase {
+    fn default() -> Self {
+        let mut res = Self { storage: Default::default() };
+        init_files_group(&mut res);
+        res
+    }
+}
+impl AsFilesGroupMut for ContractExpansionDatabase {
+    fn as_files_group_mut(&mut self) -> &mut (dyn FilesGroup + 'static) {
+        self
+    }
+}
+impl Upcast<dyn FilesGroup> for ContractExpansionDatabase {
+    fn upcast(&self) -> &(dyn FilesGroup + 'static) {
+        self
+    }
+}
+impl Upcast<dyn SyntaxGroup> for ContractExpansionDatabase {
+    fn upcast(&self) -> &(dyn SyntaxGroup + 'static) {
+        self
+    }
+}
+
+/// Expands the `dojo::contract` modules of the given Cairo code, without a full Scarb build.
+///
+/// The modules go through the same expansion as in the Dojo plugin, and the generated code of
+/// all of them is returned along with their diagnostics. Modules without the `dojo::contract`
+/// attribute are ignored.
+pub fn expand_contract(cairo_code: &str) -> (String, Vec<PluginDiagnostic>) {
+    let db = &mut ContractExpansionDatabase::default();
+
+    let file_id = db.intern_file(FileLongId::Virtual(VirtualFile {
+        parent: None,
+        name: "lib.cairo".into(),
+        content: Arc::new(cairo_code.into()),
+        code_mappings: Default::default(),
+        kind: FileKind::Module,
+    }));
+
+    let mut code = String::new();
+    let mut diagnostics = vec![];
+
+    let Ok(syntax_file) = db.file_syntax(file_id) else {
+        return (code, diagnostics);
     };
+    let syntax_file = ast::SyntaxFile::from_syntax_node(db, syntax_file);
 
-    #[allow(clippy::collapsible_match)]
-    match &attr.args[..] {
-        [AttributeArg { variant: AttributeArgVariant::Unnamed(value), .. }] => match value {
-            ast::Expr::Path(path)
-                if path.as_syntax_node().get_text_without_trivia(db) == ALLOW_REF_SELF_ARG =>
-            {
-                Some(true)
-            }
-            _ => None,
+    for item in syntax_file.items(db).elements(db) {
+        let ast::ModuleItem::Module(module_ast) = item else {
+            continue;
+        };
+
+        if !module_ast.has_attr(db, DOJO_CONTRACT_ATTR) {
+            continue;
+        }
+
+        let result = DojoContract::from_module(db, module_ast);
+
+        if let Some(generated) = result.code {
+            code.push_str(&generated.content);
+        }
+        diagnostics.extend(result.diagnostics);
+    }
+
+    (code, diagnostics)
+}
+
+/// Extracts the model names of the struct constructors passed to `set!` or `delete!`.
+///
+/// Models passed through variables can't be resolved from the syntax only and are ignored.
+fn extract_model_ctors(db: &dyn SyntaxGroup, expression: &ast::Expr) -> Vec<SmolStr> {
+    match expression {
+        ast::Expr::Tuple(tuple) => tuple
+            .expressions(db)
+            .elements(db)
+            .iter()
+            .flat_map(|element| extract_model_ctors(db, element))
+            .collect(),
+        ast::Expr::Parenthesized(parenthesized) => extract_model_ctors(db, &parenthesized.expr(db)),
+        ast::Expr::StructCtorCall(ctor) => match ctor.path(db).elements(db).last() {
+            Some(ast::PathSegment::Simple(segment)) => vec![segment.ident(db).text(db)],
+            Some(ast::PathSegment::WithGenericArgs(segment)) => vec![segment.ident(db).text(db)],
+            None => vec![],
         },
+        _ => vec![],
+    }
+}
+
+/// Gets the names of the models declared in the file of the provided module, along with the names
+/// imported by the `use` items of the file (or their aliases), at any depth.
+fn get_file_models_and_imports(
+    db: &dyn SyntaxGroup,
+    module_ast: &ast::ItemModule,
+) -> (HashSet<String>, HashSet<String>) {
+    fn collect(
+        db: &dyn SyntaxGroup,
+        node: SyntaxNode,
+        models: &mut HashSet<String>,
+        imports: &mut HashSet<String>,
+    ) {
+        match node.kind(db) {
+            SyntaxKind::ItemStruct => {
+                let struct_ast = ast::ItemStruct::from_syntax_node(db, node);
+                if struct_ast.has_attr(db, DOJO_MODEL_ATTR) {
+                    models.insert(struct_ast.name(db).text(db).to_string());
+                }
+            }
+            // `Name` or `Name as Alias`, the last word being the imported name
+            SyntaxKind::UsePathLeaf => {
+                let text = node.get_text_without_trivia(db);
+                if let Some(name) = text.split_whitespace().last() {
+                    imports.insert(name.to_string());
+                }
+            }
+            _ => {
+                for child in db.get_children(node).iter() {
+                    collect(db, child.clone(), models, imports);
+                }
+            }
+        }
+    }
+
+    let mut root = module_ast.as_syntax_node();
+    while let Some(parent) = root.parent() {
+        root = parent;
+    }
+
+    let mut models = HashSet::new();
+    let mut imports = HashSet::new();
+    collect(db, root, &mut models, &mut imports);
+    (models, imports)
+}
+
+/// Gets the names of the functions declared in the body of a contract, as free functions or in its
+/// impls.
+fn contract_function_names(db: &dyn SyntaxGroup, body: &ast::ModuleBody) -> HashSet<String> {
+    let mut names = HashSet::new();
+
+    for item in body.items(db).elements(db) {
+        match item {
+            ast::ModuleItem::FreeFunction(fn_ast) => {
+                names.insert(fn_ast.declaration(db).name(db).text(db).to_string());
+            }
+            ast::ModuleItem::Impl(impl_ast) => {
+                if let ast::MaybeImplBody::Some(impl_body) = impl_ast.body(db) {
+                    for impl_item in impl_body.items(db).elements(db) {
+                        if let ast::ImplItem::Function(fn_ast) = impl_item {
+                            names.insert(fn_ast.declaration(db).name(db).text(db).to_string());
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    names
+}
+
+/// Computes the Levenshtein distance between two names, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut distances: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut previous = distances[0];
+        distances[0] = i + 1;
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a_char != *b_char);
+            previous = distances[j + 1];
+            distances[j + 1] = substitution.min(distances[j] + 1).min(previous + 1);
+        }
+    }
+
+    distances[b.len()]
+}
+
+/// Checks that the name of a contract module doesn't clash with the generated items.
+fn check_contract_name(
+    db: &dyn SyntaxGroup,
+    module_ast: &ast::ItemModule,
+) -> Option<PluginDiagnostic> {
+    let name = module_ast.name(db).text(db);
+
+    is_name_reserved(&name).then(|| PluginDiagnostic {
+        stable_ptr: module_ast.name(db).stable_ptr().untyped(),
+        message: format!("The contract name '{}' is a reserved identifier.", name),
+        severity: Severity::Error,
+    })
+}
+
+/// Checks if the provided trait name follows the interface naming convention (`IActions`).
+fn is_interface_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next() == Some('I') && chars.next().is_some_and(|c| c.is_ascii_uppercase())
+}
+
+/// Checks if the provided name is a valid Cairo identifier.
+fn is_name_valid(name: &str) -> bool {
+    !name.is_empty() && find_invalid_char(name).is_none()
+}
+
+/// Finds the first character which can't be used at its position in a Cairo identifier, along
+/// with its position (in characters) in the provided name.
+fn find_invalid_char(name: &str) -> Option<(usize, char)> {
+    name.chars().enumerate().find(|(idx, c)| {
+        let is_valid = if *idx == 0 { c.is_ascii_alphabetic() } else { c.is_ascii_alphanumeric() };
+        !is_valid && *c != '_'
+    })
+}
+
+/// Checks if the provided name is a reserved identifier.
+fn is_name_reserved(name: &str) -> bool {
+    RESERVED_NAMES.contains(&name)
+}
+
+/// Get the contract version from the `Expr` parameter.
+///
+/// The version may be provided as a string literal or as a short string.
+fn get_contract_version(
+    db: &dyn SyntaxGroup,
+    arg_value: Expr,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> String {
+    match get_string_argument(db, &arg_value) {
+        Some(version) if !version.is_empty() && !version.contains(['"', '\\']) => version,
+        _ => {
+            diagnostics.push(PluginDiagnostic {
+                message: format!(
+                    "The argument '{}' of dojo::contract must be a string",
+                    CONTRACT_VERSION_NAME
+                ),
+                stable_ptr: arg_value.stable_ptr().untyped(),
+                severity: Severity::Error,
+            });
+            DEFAULT_CONTRACT_VERSION.to_string()
+        }
+    }
+}
+
+/// Get the value of a boolean argument (`expose_deps`, `upgradeable`, `pausable`) from the `Expr`
+/// parameter, defaulting to false if the value is not a boolean.
+fn get_bool_argument(
+    arg_name: &str,
+    arg_value: Expr,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> bool {
+    match arg_value {
+        Expr::True(_) => true,
+        Expr::False(_) => false,
+        _ => {
+            diagnostics.push(PluginDiagnostic {
+                message: format!("The argument '{}' of dojo::contract must be a boolean", arg_name),
+                stable_ptr: arg_value.stable_ptr().untyped(),
+                severity: Severity::Error,
+            });
+            false
+        }
+    }
+}
+
+/// Get the text of a string argument given as a string literal or as a short string from the `Expr`
+/// parameter, or `None` if the parameter is not a string.
+fn get_string_argument(db: &dyn SyntaxGroup, arg_value: &Expr) -> Option<String> {
+    match arg_value {
+        Expr::String(value) => Some(value.text(db).trim_matches('"').to_string()),
+        Expr::ShortString(value) => Some(value.text(db).trim_matches('\'').to_string()),
         _ => None,
     }
 }
+
+/// Get the strings of a list given as `Expr` parameter (`["Moves", "Position"]`), or `None` if the
+/// parameter is not a list of strings.
+fn get_string_list(db: &dyn SyntaxGroup, arg_value: &Expr) -> Option<Vec<String>> {
+    let text = arg_value.as_syntax_node().get_text_without_trivia(db);
+    let list = text.strip_prefix('[')?.strip_suffix(']')?;
+
+    list.split(',')
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
+        .map(|element| element.strip_prefix('"')?.strip_suffix('"').map(str::to_string))
+        .collect()
+}
+
+/// Get the names of the models written by the contract from the `Expr` parameter, given as a list
+/// of strings (`writes: ["Moves", "Position"]`).
+fn get_writes(
+    db: &dyn SyntaxGroup,
+    arg_value: Expr,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> Vec<String> {
+    let Some(names) = get_string_list(db, &arg_value) else {
+        diagnostics.push(PluginDiagnostic {
+            message: format!(
+                "The argument '{}' of dojo::contract must be a list of model names",
+                WRITES_NAME
+            ),
+            stable_ptr: arg_value.stable_ptr().untyped(),
+            severity: Severity::Error,
+        });
+        return vec![];
+    };
+
+    names
+        .into_iter()
+        .filter(|name| {
+            let is_valid = is_name_valid(name);
+            if !is_valid {
+                diagnostics.push(PluginDiagnostic {
+                    message: format!(
+                        "The model name '{}' of the argument '{}' of dojo::contract must be a \
+                         valid identifier",
+                        name, WRITES_NAME
+                    ),
+                    stable_ptr: arg_value.stable_ptr().untyped(),
+                    severity: Severity::Error,
+                });
+            }
+            is_valid
+        })
+        .collect()
+}
+
+/// Get the access roles of the contract from the `Expr` parameter, given as a list of strings
+/// (`roles: ["admin", "player"]`). The roles are only tags for the tools granting the permissions,
+/// each one being kept once in the order of declaration.
+fn get_roles(
+    db: &dyn SyntaxGroup,
+    arg_value: Expr,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> Vec<String> {
+    let Some(roles) = get_string_list(db, &arg_value) else {
+        diagnostics.push(PluginDiagnostic {
+            message: format!(
+                "The argument '{}' of dojo::contract must be a list of role names",
+                ROLES_NAME
+            ),
+            stable_ptr: arg_value.stable_ptr().untyped(),
+            severity: Severity::Error,
+        });
+        return vec![];
+    };
+
+    let mut unique_roles: Vec<String> = vec![];
+    for role in roles {
+        if !is_name_valid(&role) {
+            diagnostics.push(PluginDiagnostic {
+                message: format!(
+                    "The role '{}' of the argument '{}' of dojo::contract must be a valid \
+                     identifier",
+                    role, ROLES_NAME
+                ),
+                stable_ptr: arg_value.stable_ptr().untyped(),
+                severity: Severity::Error,
+            });
+        } else if !unique_roles.contains(&role) {
+            unique_roles.push(role);
+        }
+    }
+
+    unique_roles
+}
+
+/// Get the prefix of the event variants injected by dojo::contract from the `Expr` parameter,
+/// which must make valid identifiers of them (`event_prefix: "Foo"` for `FooUpgradeableEvent`).
+fn get_event_prefix(
+    db: &dyn SyntaxGroup,
+    arg_value: Expr,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> String {
+    match get_string_argument(db, &arg_value) {
+        Some(prefix) if is_name_valid(&prefix) => prefix,
+        _ => {
+            diagnostics.push(PluginDiagnostic {
+                message: format!(
+                    "The argument '{}' of dojo::contract must be a valid identifier",
+                    EVENT_PREFIX_NAME
+                ),
+                stable_ptr: arg_value.stable_ptr().untyped(),
+                severity: Severity::Error,
+            });
+            String::new()
+        }
+    }
+}
+
+/// Get the name of the world storage field from the `Expr` parameter.
+fn get_world_field(
+    db: &dyn SyntaxGroup,
+    arg_value: Expr,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> String {
+    match get_string_argument(db, &arg_value) {
+        Some(world_field) if is_name_valid(&world_field) && !is_name_reserved(&world_field) => {
+            world_field
+        }
+        world_field => {
+            // point at the offending character, if any, as it may be hard to spot in the name
+            let invalid_char = world_field.as_deref().and_then(find_invalid_char);
+
+            diagnostics.push(PluginDiagnostic {
+                message: match invalid_char {
+                    Some((position, c)) => format!(
+                        "The argument '{}' of dojo::contract must be a valid identifier, the \
+                         character '{}' at position {} is not allowed",
+                        WORLD_FIELD_NAME, c, position
+                    ),
+                    None => format!(
+                        "The argument '{}' of dojo::contract must be a valid identifier",
+                        WORLD_FIELD_NAME
+                    ),
+                },
+                stable_ptr: arg_value.stable_ptr().untyped(),
+                severity: Severity::Error,
+            });
+            DEFAULT_WORLD_FIELD.to_string()
+        }
+    }
+}
+
+/// Get parameters of the dojo::contract attribute.
+///
+/// Parameters:
+/// * db: The semantic database.
+/// * module_ast: The AST of the contract module.
+/// * diagnostics: vector of compiler diagnostics.
+///
+/// Returns:
+/// * A [`ContractParameters`] object containing all the dojo::contract parameters with their
+/// default values if not set in the code.
+fn get_parameters(
+    db: &dyn SyntaxGroup,
+    module_ast: &ast::ItemModule,
+    diagnostics: &mut Vec<PluginDiagnostic>,
+) -> ContractParameters {
+    let mut parameters = ContractParameters::default();
+    let mut processed_args: HashMap<String, bool> = HashMap::new();
+
+    let Some(attr) = module_ast.attributes(db).query_attr(db, DOJO_CONTRACT_ATTR).first().cloned()
+    else {
+        return parameters;
+    };
+
+    if let OptionArgListParenthesized::ArgListParenthesized(arguments) = attr.arguments(db) {
+        arguments.arguments(db).elements(db).iter().for_each(|a| match a.arg_clause(db) {
+            ArgClause::Named(x) => {
+                let arg_name = x.name(db).text(db).to_string();
+                let arg_value = x.value(db);
+
+                if processed_args.contains_key(&arg_name) {
+                    diagnostics.push(PluginDiagnostic {
+                        message: format!("Too many '{}' attributes for dojo::contract", arg_name),
+                        stable_ptr: module_ast.stable_ptr().untyped(),
+                        severity: Severity::Error,
+                    });
+                } else {
+                    processed_args.insert(arg_name.clone(), true);
+
+                    match arg_name.as_str() {
+                        CONTRACT_VERSION_NAME => {
+                            parameters.version = get_contract_version(db, arg_value, diagnostics);
+                        }
+                        WORLD_FIELD_NAME => {
+                            parameters.world_field = get_world_field(db, arg_value, diagnostics);
+                        }
+                        EXPOSE_DEPS_NAME => {
+                            parameters.expose_deps =
+                                get_bool_argument(EXPOSE_DEPS_NAME, arg_value, diagnostics);
+                        }
+                        UPGRADEABLE_NAME => {
+                            parameters.upgradeable =
+                                get_bool_argument(UPGRADEABLE_NAME, arg_value, diagnostics);
+                        }
+                        WRITES_NAME => {
+                            parameters.writes = get_writes(db, arg_value, diagnostics);
+                        }
+                        ROLES_NAME => {
+                            parameters.roles = get_roles(db, arg_value, diagnostics);
+                        }
+                        PAUSABLE_NAME => {
+                            parameters.pausable =
+                                get_bool_argument(PAUSABLE_NAME, arg_value, diagnostics);
+                        }
+                        EVENT_PREFIX_NAME => {
+                            parameters.event_prefix = get_event_prefix(db, arg_value, diagnostics);
+                        }
+                        EXPOSE_STORAGE_NAME => {
+                            parameters.expose_storage =
+                                get_bool_argument(EXPOSE_STORAGE_NAME, arg_value, diagnostics);
+                        }
+                        _ => {
+                            diagnostics.push(PluginDiagnostic {
+                                message: format!(
+                                    "Unexpected argument '{}' for dojo::contract",
+                                    arg_name
+                                ),
+                                stable_ptr: x.stable_ptr().untyped(),
+                                severity: Severity::Warning,
+                            });
+                        }
+                    }
+                }
+            }
+            ArgClause::Unnamed(x) => match x.value(db) {
+                Expr::Path(path)
+                    if path.as_syntax_node().get_text_without_trivia(db) == ALLOW_REF_SELF_ARG =>
+                {
+                    parameters.allow_ref_self = true;
+                }
+                _ => {
+                    diagnostics.push(PluginDiagnostic {
+                        message: format!(
+                            "Unexpected argument '{}' for dojo::contract",
+                            x.as_syntax_node().get_text(db)
+                        ),
+                        stable_ptr: x.stable_ptr().untyped(),
+                        severity: Severity::Warning,
+                    });
+                }
+            },
+            ArgClause::FieldInitShorthand(x) => {
+                diagnostics.push(PluginDiagnostic {
+                    message: format!(
+                        "Unexpected argument '{}' for dojo::contract",
+                        x.name(db).name(db).text(db).to_string()
+                    ),
+                    stable_ptr: x.stable_ptr().untyped(),
+                    severity: Severity::Warning,
+                });
+            }
+        })
+    }
+
+    parameters
+}
+
+#[test]
+pub fn test_is_name_reserved() {
+    for name in RESERVED_NAMES {
+        assert!(is_name_reserved(name), "'{}' should be reserved", name);
+    }
+
+    assert!(!is_name_reserved("actions"));
+    assert!(!is_name_reserved("World"));
+    assert!(!is_name_reserved("worlds"));
+}
+
+#[test]
+pub fn test_is_name_valid() {
+    assert!(is_name_valid("world_dispatcher"));
+    assert!(is_name_valid("_world"));
+    assert!(is_name_valid("world2"));
+
+    assert!(!is_name_valid(""));
+    assert!(!is_name_valid("2world"));
+    assert!(!is_name_valid("my-world"));
+    assert!(!is_name_valid("my world"));
+}
+
+#[test]
+pub fn test_find_invalid_char() {
+    assert_eq!(find_invalid_char("world_dispatcher"), None);
+    assert_eq!(find_invalid_char("_world2"), None);
+
+    assert_eq!(find_invalid_char("my world"), Some((2, ' ')));
+    assert_eq!(find_invalid_char("my-world"), Some((2, '-')));
+    assert_eq!(find_invalid_char("wörld"), Some((1, 'ö')));
+    assert_eq!(find_invalid_char("2world"), Some((0, '2')));
+}
+
+#[test]
+pub fn test_is_interface_name() {
+    assert!(is_interface_name("IActions"));
+    assert!(is_interface_name("IWorld"));
+
+    assert!(!is_interface_name("I"));
+    assert!(!is_interface_name("Internal"));
+    assert!(!is_interface_name("HelperTrait"));
+}
@@ -53,6 +53,8 @@ pub struct Model {
 pub struct SystemAuxData {
     pub name: SmolStr,
     pub dependencies: Vec<Dependency>,
+    pub version: String,
+    pub roles: Vec<String>,
 }
 
 /// Dojo related auxiliary data of the Dojo plugin.
@@ -3,21 +3,26 @@ use std::sync::Arc;
 use cairo_lang_defs::db::{DefsDatabase, DefsGroup};
 use cairo_lang_defs::ids::ModuleId;
 use cairo_lang_defs::plugin::MacroPlugin;
+use cairo_lang_diagnostics::Severity;
 use cairo_lang_filesystem::cfg::CfgSet;
 use cairo_lang_filesystem::db::{
     init_files_group, AsFilesGroupMut, CrateConfiguration, FilesDatabase, FilesGroup, FilesGroupEx,
 };
 use cairo_lang_filesystem::ids::{CrateLongId, Directory, FileLongId};
-use cairo_lang_parser::db::ParserDatabase;
+use cairo_lang_parser::db::{ParserDatabase, ParserGroup};
 use cairo_lang_plugins::get_base_plugins;
 use cairo_lang_plugins::test_utils::expand_module_text;
 use cairo_lang_syntax::node::db::{SyntaxDatabase, SyntaxGroup};
+use cairo_lang_syntax::node::{ast, TypedSyntaxNode};
 use cairo_lang_test_utils::parse_test_file::TestRunnerResult;
 use cairo_lang_test_utils::verify_diagnostics_expectation;
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
 use cairo_lang_utils::Upcast;
+use dojo_types::system::Dependency;
+use indoc::indoc;
 
-use super::BuiltinDojoPlugin;
+use super::{BuiltinDojoPlugin, DojoAuxData, SystemAuxData};
+use crate::contract::{expand_contract, DojoContract};
 
 cairo_lang_test_utils::test_file_test!(
     expand_plugin,
@@ -27,6 +32,8 @@ cairo_lang_test_utils::test_file_test!(
         print: "print",
         introspect: "introspect",
         system: "system",
+        contract: "contract",
+        contract_parameters: "contract_parameters",
     },
     test_expand_plugin
 );
@@ -115,3 +122,222 @@ pub fn test_expand_plugin_inner(
         error,
     }
 }
+
+/// Calls `f` with the aux data of the systems of the contracts defined in the given code.
+fn for_each_contract_system(cairo_code: &str, mut f: impl FnMut(&SystemAuxData)) {
+    let db = &mut DatabaseForTesting::default();
+
+    let file_id = db.intern_file(FileLongId::OnDisk("test_src/lib.cairo".into()));
+    db.as_files_group_mut().override_file_content(file_id, Some(Arc::new(cairo_code.into())));
+
+    let syntax_file = ast::SyntaxFile::from_syntax_node(db, db.file_syntax(file_id).unwrap());
+
+    for item in syntax_file.items(db).elements(db) {
+        let ast::ModuleItem::Module(module_ast) = item else {
+            continue;
+        };
+
+        let aux_data = DojoContract::from_module(db, module_ast).code.unwrap().aux_data.unwrap();
+        let aux_data = aux_data.0.as_any().downcast_ref::<DojoAuxData>().unwrap();
+
+        aux_data.systems.iter().for_each(&mut f);
+    }
+}
+
+/// Returns the model dependencies of the contracts defined in the given code, sorted by name.
+fn get_contract_dependencies(cairo_code: &str) -> Vec<Dependency> {
+    let mut dependencies = vec![];
+    for_each_contract_system(cairo_code, |system| {
+        dependencies.extend(system.dependencies.iter().cloned());
+    });
+
+    dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+    dependencies
+}
+
+#[test]
+fn test_contract_dependencies() {
+    let dependencies = get_contract_dependencies(indoc! {"
+        #[dojo::contract]
+        mod actions {
+            #[abi(embed_v0)]
+            impl ActionsImpl of IActions<ContractState> {
+                fn read_only(world: IWorldDispatcher, player: ContractAddress) {
+                    let position = get!(world, player, (Position));
+                }
+
+                fn read_write(world: IWorldDispatcher, player: ContractAddress) {
+                    let moves = get!(world, player, Moves);
+                    set!(world, (Moves { player, remaining: moves.remaining - 1 }));
+                }
+
+                fn write_only(world: IWorldDispatcher, player: ContractAddress) {
+                    set!(world, (Vec2 { player, x: 0, y: 0 }, Moves { player, remaining: 0 }));
+                }
+            }
+        }
+    "});
+
+    assert_eq!(
+        dependencies,
+        vec![
+            Dependency { name: "Moves".into(), read: true, write: true },
+            Dependency { name: "Position".into(), read: true, write: false },
+            Dependency { name: "Vec2".into(), read: false, write: true },
+        ]
+    );
+}
+
+#[test]
+fn test_contract_declared_writes() {
+    let dependencies = get_contract_dependencies(indoc! {r#"
+        #[dojo::contract(writes: ["Moves", "Position"])]
+        mod actions {
+            #[abi(embed_v0)]
+            impl ActionsImpl of IActions<ContractState> {
+                fn read_write(world: IWorldDispatcher, player: ContractAddress) {
+                    let moves = get!(world, player, Moves);
+                    set!(world, (Moves { player, remaining: moves.remaining - 1 }));
+                }
+
+                fn read_only(world: IWorldDispatcher, player: ContractAddress) {
+                    let config = get!(world, player, Config);
+                }
+            }
+        }
+    "#});
+
+    // the declared writes are merged with the reads and writes of the systems
+    assert_eq!(
+        dependencies,
+        vec![
+            Dependency { name: "Config".into(), read: true, write: false },
+            Dependency { name: "Moves".into(), read: true, write: true },
+            Dependency { name: "Position".into(), read: false, write: true },
+        ]
+    );
+}
+
+#[test]
+fn test_contract_roles() {
+    let mut roles = vec![];
+    for_each_contract_system(
+        indoc! {r#"
+            #[dojo::contract(roles: ["admin", "player", "admin"])]
+            mod actions {}
+
+            #[dojo::contract]
+            mod spawner {}
+        "#},
+        |system| roles.push((system.name.to_string(), system.roles.clone())),
+    );
+
+    // the roles are kept once, in the order of declaration
+    assert_eq!(
+        roles,
+        vec![
+            ("actions".to_string(), vec!["admin".to_string(), "player".to_string()]),
+            ("spawner".to_string(), vec![]),
+        ]
+    );
+}
+
+#[test]
+fn test_expand_contract() {
+    let (code, diagnostics) = expand_contract(indoc! {"
+        #[dojo::contract]
+        mod actions {
+            #[abi(embed_v0)]
+            impl ActionsImpl of IActions<ContractState> {
+                fn spawn(world: IWorldDispatcher) {}
+            }
+        }
+
+        mod helpers {}
+    "});
+
+    assert!(diagnostics.is_empty());
+    assert!(code.contains("mod actions {"));
+    assert!(code.contains("let world = self.world_dispatcher.read();"));
+    assert!(!code.contains("mod helpers"));
+
+    // modules which are not dojo contracts are not expanded
+    let (code, diagnostics) = expand_contract("mod helpers {}");
+    assert!(code.is_empty());
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn test_expand_contract_diagnostics() {
+    let (code, diagnostics) = expand_contract(indoc! {"
+        #[dojo::contract]
+        mod world {}
+    "});
+
+    assert!(code.is_empty());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "The contract name 'world' is a reserved identifier.");
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+
+    // the warnings are not reported as errors, and don't prevent the expansion
+    let (code, diagnostics) = expand_contract(indoc! {"
+        #[dojo::contract]
+        mod actions {
+            struct Storage {}
+        }
+    "});
+
+    assert!(code.contains("mod actions {"));
+    assert!(!diagnostics.is_empty());
+    assert!(diagnostics.iter().all(|diagnostic| diagnostic.severity == Severity::Warning));
+}
+
+#[test]
+fn test_contract_diagnostics_only() {
+    let db = &mut DatabaseForTesting::default();
+
+    let file_id = db.intern_file(FileLongId::OnDisk("test_src/lib.cairo".into()));
+    let cairo_code = indoc! {"
+        #[dojo::model]
+        struct Position {
+            #[key]
+            player: ContractAddress,
+            x: u32,
+        }
+
+        #[dojo::contract(version: \"1.0.0\", version: \"2.0.0\")]
+        mod actions {
+            #[abi(embed_v0)]
+            impl ActionsImpl of IActions<ContractState> {
+                fn spawn(world: IWorldDispatcher, player: ContractAddress) {
+                    let world = IWorldDispatcher { contract_address: player };
+                    set!(world, (Postion { player, x: 0 }));
+                }
+            }
+        }
+
+        #[dojo::contract]
+        mod empty {}
+
+        #[dojo::contract]
+        mod world {}
+    "};
+    db.as_files_group_mut().override_file_content(file_id, Some(Arc::new(cairo_code.into())));
+
+    let syntax_file = ast::SyntaxFile::from_syntax_node(db, db.file_syntax(file_id).unwrap());
+    let mut diagnostics_count = 0;
+
+    for item in syntax_file.items(db).elements(db) {
+        let ast::ModuleItem::Module(module_ast) = item else {
+            continue;
+        };
+
+        let diagnostics = DojoContract::from_module(db, module_ast.clone()).diagnostics;
+        assert!(!diagnostics.is_empty());
+        assert_eq!(DojoContract::diagnostics_only(db, module_ast), diagnostics);
+
+        diagnostics_count += diagnostics.len();
+    }
+
+    assert_eq!(diagnostics_count, 5);
+}
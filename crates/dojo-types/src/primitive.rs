@@ -47,6 +47,8 @@ pub enum PrimitiveError {
     UnsupportedType,
     #[error("Set value type mismatch")]
     TypeMismatch,
+    #[error("Option {option} of enum {name} is out of range, the enum has {options} options")]
+    EnumOptionOutOfRange { name: String, option: u8, options: usize },
     #[error(transparent)]
     ValueOutOfRange(#[from] ValueOutOfRangeError),
     #[error(transparent)]
@@ -176,14 +176,24 @@ impl Ty {
                 }
             }
             Ty::Enum(e) => {
-                e.option =
-                    Some(felts.remove(0).try_into().map_err(PrimitiveError::ValueOutOfRange)?);
+                let option: u8 =
+                    felts.remove(0).try_into().map_err(PrimitiveError::ValueOutOfRange)?;
+
+                // the data may have been written with other options than the ones of the schema
+                if option as usize >= e.options.len() {
+                    return Err(PrimitiveError::EnumOptionOutOfRange {
+                        name: e.name.clone(),
+                        option,
+                        options: e.options.len(),
+                    });
+                }
+                e.option = Some(option);
 
-                match &e.options[e.option.unwrap() as usize].ty {
+                match &e.options[option as usize].ty {
                     // Skip deserializing the enum option if it has no type - unit type
                     Ty::Tuple(tuple) if tuple.is_empty() => {}
                     _ => {
-                        e.options[e.option.unwrap() as usize].ty.deserialize(felts)?;
+                        e.options[option as usize].ty.deserialize(felts)?;
                     }
                 }
             }
@@ -422,3 +432,36 @@ fn format_member(m: &Member) -> String {
 
     str
 }
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::types::FieldElement;
+
+    use super::{Enum, EnumOption, Ty};
+    use crate::primitive::{Primitive, PrimitiveError};
+
+    #[test]
+    fn deserialize_enum_option_out_of_range() {
+        let mut ty = Ty::Enum(Enum {
+            name: "Direction".into(),
+            option: None,
+            options: vec![
+                EnumOption { name: "Left".into(), ty: Ty::Tuple(vec![]) },
+                EnumOption { name: "Right".into(), ty: Ty::Primitive(Primitive::U8(None)) },
+            ],
+        });
+
+        ty.deserialize(&mut vec![FieldElement::ONE, FieldElement::TWO]).unwrap();
+        assert_eq!(ty.as_enum().unwrap().option().unwrap(), "Right");
+
+        let error = ty.deserialize(&mut vec![FieldElement::TWO]).unwrap_err();
+        assert!(matches!(
+            error,
+            PrimitiveError::EnumOptionOutOfRange { option: 2, options: 2, .. }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Option 2 of enum Direction is out of range, the enum has 2 options"
+        );
+    }
+}
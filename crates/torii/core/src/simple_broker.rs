@@ -14,8 +14,13 @@ static SUBSCRIBERS: Lazy<Mutex<HashMap<TypeId, Box<dyn Any + Send>>>> = Lazy::ne
 
 pub struct Senders<T>(pub Slab<UnboundedSender<T>>);
 
+/// The senders of the subscription streams, by the scope they are subscribed to.
+pub struct ScopedSenders<T>(pub HashMap<String, Slab<UnboundedSender<T>>>);
+
 struct BrokerStream<T: Sync + Send + Clone + 'static>(usize, UnboundedReceiver<T>);
 
+struct ScopedBrokerStream<T: Sync + Send + Clone + 'static>(String, usize, UnboundedReceiver<T>);
+
 fn with_senders<T, F, R>(f: F) -> R
 where
     T: Sync + Send + Clone + 'static,
@@ -28,6 +33,18 @@ where
     f(senders.downcast_mut::<Senders<T>>().unwrap())
 }
 
+fn with_scoped_senders<T, F, R>(f: F) -> R
+where
+    T: Sync + Send + Clone + 'static,
+    F: FnOnce(&mut ScopedSenders<T>) -> R,
+{
+    let mut map = SUBSCRIBERS.lock().unwrap();
+    let senders = map
+        .entry(TypeId::of::<ScopedSenders<T>>())
+        .or_insert_with(|| Box::new(ScopedSenders::<T>(Default::default())));
+    f(senders.downcast_mut::<ScopedSenders<T>>().unwrap())
+}
+
 impl<T: Sync + Send + Clone + 'static> Drop for BrokerStream<T> {
     fn drop(&mut self) {
         with_senders::<T, _, _>(|senders| senders.0.remove(self.0));
@@ -42,6 +59,27 @@ impl<T: Sync + Send + Clone + 'static> Stream for BrokerStream<T> {
     }
 }
 
+impl<T: Sync + Send + Clone + 'static> Drop for ScopedBrokerStream<T> {
+    fn drop(&mut self) {
+        with_scoped_senders::<T, _, _>(|senders| {
+            if let Some(scope_senders) = senders.0.get_mut(&self.0) {
+                scope_senders.remove(self.1);
+                if scope_senders.is_empty() {
+                    senders.0.remove(&self.0);
+                }
+            }
+        });
+    }
+}
+
+impl<T: Sync + Send + Clone + 'static> Stream for ScopedBrokerStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.2.poll_next_unpin(cx)
+    }
+}
+
 /// A simple broker based on memory
 pub struct SimpleBroker<T>(PhantomData<T>);
 
@@ -55,6 +93,20 @@ impl<T: Sync + Send + Clone + 'static> SimpleBroker<T> {
         });
     }
 
+    /// Publish a message in the given scope. It is received by the subscription streams of this
+    /// scope only, along with the unscoped subscription streams.
+    pub fn publish_scoped(scope: &str, msg: T) {
+        with_scoped_senders::<T, _, _>(|senders| {
+            if let Some(scope_senders) = senders.0.get_mut(scope) {
+                for (_, sender) in scope_senders.iter_mut() {
+                    sender.start_send(msg.clone()).ok();
+                }
+            }
+        });
+
+        Self::publish(msg);
+    }
+
     /// Subscribe to the message of the specified type and returns a `Stream`.
     pub fn subscribe() -> impl Stream<Item = T> {
         with_senders::<T, _, _>(|senders| {
@@ -64,6 +116,16 @@ impl<T: Sync + Send + Clone + 'static> SimpleBroker<T> {
         })
     }
 
+    /// Subscribe to the messages of the specified type published in the given scope, and returns
+    /// a `Stream`. The subscription is not woken up by the messages of the other scopes.
+    pub fn subscribe_scoped(scope: &str) -> impl Stream<Item = T> {
+        with_scoped_senders::<T, _, _>(|senders| {
+            let (tx, rx) = mpsc::unbounded();
+            let id = senders.0.entry(scope.to_string()).or_default().insert(tx);
+            ScopedBrokerStream(scope.to_string(), id, rx)
+        })
+    }
+
     /// Execute the given function with the _subscribers_ of the specified subscription type.
     pub fn with_subscribers<F, R>(f: F) -> R
     where
@@ -18,8 +18,8 @@ use crate::model::ModelSQLReader;
 use crate::query_queue::{Argument, QueryQueue};
 use crate::simple_broker::SimpleBroker;
 use crate::types::{
-    Entity as EntityUpdated, Event as EventEmitted, EventMessage as EventMessageUpdated,
-    Model as ModelRegistered,
+    Entity as EntityUpdated, EntityDeleted, Event as EventEmitted,
+    EventMessage as EventMessageUpdated, Model as ModelRegistered,
 };
 use crate::utils::{must_utc_datetime_from_timestamp, utc_dt_string_from_timestamp};
 
@@ -49,6 +49,20 @@ impl Sql {
             vec![Argument::FieldElement(world_address), Argument::FieldElement(world_address)],
         );
 
+        // The tables of the models nested in arrays used to be indexed on a single `idx` column,
+        // which is now the `idx_0` column of the outermost array. The legacy columns are renamed
+        // in place, SQLite updating the primary and foreign keys referring to them.
+        let legacy_tables: Vec<(String,)> = sqlx::query_as(
+            "SELECT m.name FROM sqlite_master AS m, pragma_table_info(m.name) AS p WHERE m.type = \
+             'table' AND p.name = 'idx'",
+        )
+        .fetch_all(&pool)
+        .await?;
+        for (table_id,) in legacy_tables {
+            query_queue
+                .enqueue(format!("ALTER TABLE [{table_id}] RENAME COLUMN idx TO idx_0"), vec![]);
+        }
+
         query_queue.execute_all().await?;
 
         Ok(Self { pool, world_address, query_queue })
@@ -130,8 +144,8 @@ impl Sql {
             vec![model.name()],
             &mut model_idx,
             block_timestamp,
-            false,
-            false,
+            0,
+            0,
         );
         self.query_queue.execute_all().await?;
 
@@ -171,7 +185,7 @@ impl Sql {
                                ?, ?, ?) ON CONFLICT(id) DO UPDATE SET \
                                executed_at=EXCLUDED.executed_at, event_id=EXCLUDED.event_id \
                                RETURNING *";
-        let entity_updated: EntityUpdated = sqlx::query_as(insert_entities)
+        let mut entity_updated: EntityUpdated = sqlx::query_as(insert_entities)
             .bind(&entity_id)
             .bind(&keys_str)
             .bind(event_id)
@@ -179,6 +193,16 @@ impl Sql {
             .fetch_one(&self.pool)
             .await?;
 
+        self.query_queue.enqueue(
+            "INSERT INTO entity_history (entity_id, event_id, executed_at) VALUES (?, ?, ?) ON \
+             CONFLICT(entity_id, event_id) DO NOTHING",
+            vec![
+                Argument::String(entity_id.clone()),
+                Argument::String(event_id.to_string()),
+                Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+            ],
+        );
+
         let path = vec![entity.name()];
         self.build_set_entity_queries_recursive(
             path,
@@ -186,11 +210,13 @@ impl Sql {
             (&entity_id, false),
             &entity,
             block_timestamp,
-            None,
+            &[],
         );
         self.query_queue.execute_all().await?;
 
-        SimpleBroker::publish(entity_updated);
+        let model = entity.name();
+        entity_updated.model = model.clone();
+        SimpleBroker::publish_scoped(&model, entity_updated);
 
         Ok(())
     }
@@ -241,7 +267,7 @@ impl Sql {
             (&entity_id, true),
             &entity,
             block_timestamp,
-            None,
+            &[],
         );
         self.query_queue.execute_all().await?;
 
@@ -255,6 +281,18 @@ impl Sql {
         let path = vec![entity.name()];
         self.build_delete_entity_queries_recursive(path, &entity_id, &entity);
         self.query_queue.execute_all().await?;
+
+        let entity_deleted: Option<EntityUpdated> =
+            sqlx::query_as("SELECT * FROM entities WHERE id = ?")
+                .bind(&entity_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        if let Some(mut entity_deleted) = entity_deleted {
+            entity_deleted.model = entity.name();
+            SimpleBroker::publish(EntityDeleted(entity_deleted));
+        }
+
         Ok(())
     }
 
@@ -413,8 +451,8 @@ impl Sql {
         path: Vec<String>,
         model_idx: &mut i64,
         block_timestamp: u64,
-        is_array: bool,
-        is_parent_array: bool,
+        array_idx: usize,
+        parent_array_idx: usize,
     ) {
         if let Ty::Enum(e) = model {
             if e.options.iter().all(|o| if let Ty::Tuple(t) = &o.ty { t.is_empty() } else { false })
@@ -428,8 +466,8 @@ impl Sql {
             model,
             *model_idx,
             block_timestamp,
-            is_array,
-            is_parent_array,
+            array_idx,
+            parent_array_idx,
         );
 
         let mut build_member = |pathname: &str, member: &Ty| {
@@ -447,9 +485,10 @@ impl Sql {
                 path_clone,
                 &mut (*model_idx + 1),
                 block_timestamp,
-                // If the parent is an array, all children are also represented as table arrays
-                if let Ty::Array(_) = member { true } else { is_array },
-                if let Ty::Array(_) = model { true } else { is_parent_array },
+                // Every array adds a dimension, and all the children of an array are represented
+                // as table arrays indexed on the dimensions of their parents
+                if let Ty::Array(_) = member { array_idx + 1 } else { array_idx },
+                array_idx,
             );
         };
 
@@ -486,73 +525,73 @@ impl Sql {
         entity_id: (&str, bool),
         entity: &Ty,
         block_timestamp: u64,
-        index: Option<i64>,
+        // The position of the entity in each of the arrays it is nested in
+        indexes: &[i64],
     ) {
         let (entity_id, is_event_message) = entity_id;
 
-        let update_members =
-            |members: &[Member], query_queue: &mut QueryQueue, index: Option<i64>| {
-                let table_id = path.join("$");
-                let mut columns = vec![
-                    "id".to_string(),
-                    "event_id".to_string(),
-                    "executed_at".to_string(),
-                    "updated_at".to_string(),
-                    if is_event_message {
-                        "event_message_id".to_string()
-                    } else {
-                        "entity_id".to_string()
-                    },
-                ];
-
-                let mut arguments = vec![
-                    Argument::String(if is_event_message {
-                        "event:".to_string() + entity_id
-                    } else {
-                        entity_id.to_string()
-                    }),
-                    Argument::String(event_id.to_string()),
-                    Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
-                    Argument::String(chrono::Utc::now().to_rfc3339()),
-                    Argument::String(entity_id.to_string()),
-                ];
+        let update_members = |members: &[Member], query_queue: &mut QueryQueue, indexes: &[i64]| {
+            let table_id = path.join("$");
+            let mut columns = vec![
+                "id".to_string(),
+                "event_id".to_string(),
+                "executed_at".to_string(),
+                "updated_at".to_string(),
+                if is_event_message {
+                    "event_message_id".to_string()
+                } else {
+                    "entity_id".to_string()
+                },
+            ];
+
+            let mut arguments = vec![
+                Argument::String(if is_event_message {
+                    "event:".to_string() + entity_id
+                } else {
+                    entity_id.to_string()
+                }),
+                Argument::String(event_id.to_string()),
+                Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+                Argument::String(chrono::Utc::now().to_rfc3339()),
+                Argument::String(entity_id.to_string()),
+            ];
 
-                if let Some(idx) = index {
-                    columns.push("idx".to_string());
-                    arguments.push(Argument::Int(idx));
-                }
+            for (column_idx, idx) in indexes.iter().enumerate() {
+                columns.push(format!("idx_{}", column_idx));
+                arguments.push(Argument::Int(*idx));
+            }
 
-                for member in members.iter() {
-                    match &member.ty {
-                        Ty::Primitive(ty) => {
-                            columns.push(format!("external_{}", &member.name));
-                            arguments.push(Argument::String(ty.to_sql_value().unwrap()));
-                        }
-                        Ty::Enum(e) => {
-                            columns.push(format!("external_{}", &member.name));
-                            arguments.push(Argument::String(e.to_sql_value().unwrap()));
-                        }
-                        Ty::ByteArray(b) => {
-                            columns.push(format!("external_{}", &member.name));
-                            arguments.push(Argument::String(b.clone()));
-                        }
-                        _ => {}
+            for member in members.iter() {
+                match &member.ty {
+                    Ty::Primitive(ty) => {
+                        columns.push(format!("external_{}", &member.name));
+                        arguments.push(Argument::String(ty.to_sql_value().unwrap()));
+                    }
+                    Ty::Enum(e) => {
+                        columns.push(format!("external_{}", &member.name));
+                        arguments.push(Argument::String(e.to_sql_value().unwrap()));
+                    }
+                    Ty::ByteArray(b) => {
+                        columns.push(format!("external_{}", &member.name));
+                        arguments.push(Argument::String(b.clone()));
                     }
+                    _ => {}
                 }
+            }
 
-                let placeholders: Vec<&str> = arguments.iter().map(|_| "?").collect();
-                let statement = format!(
-                    "INSERT OR REPLACE INTO [{table_id}] ({}) VALUES ({})",
-                    columns.join(","),
-                    placeholders.join(",")
-                );
+            let placeholders: Vec<&str> = arguments.iter().map(|_| "?").collect();
+            let statement = format!(
+                "INSERT OR REPLACE INTO [{table_id}] ({}) VALUES ({})",
+                columns.join(","),
+                placeholders.join(",")
+            );
 
-                query_queue.enqueue(statement, arguments);
-            };
+            query_queue.enqueue(statement, arguments);
+        };
 
         match entity {
             Ty::Struct(s) => {
-                update_members(&s.children, &mut self.query_queue, index);
+                update_members(&s.children, &mut self.query_queue, indexes);
 
                 for member in s.children.iter() {
                     let mut path_clone = path.clone();
@@ -563,7 +602,7 @@ impl Sql {
                         (entity_id, is_event_message),
                         &member.ty,
                         block_timestamp,
-                        index,
+                        indexes,
                     );
                 }
             }
@@ -584,7 +623,7 @@ impl Sql {
                         Member { name: option.name.clone(), ty: option.ty.clone(), key: false },
                     ],
                     &mut self.query_queue,
-                    index,
+                    indexes,
                 );
 
                 match &option.ty {
@@ -599,7 +638,7 @@ impl Sql {
                             (entity_id, is_event_message),
                             &option.ty,
                             block_timestamp,
-                            index,
+                            indexes,
                         );
                     }
                 }
@@ -616,7 +655,7 @@ impl Sql {
                         .collect::<Vec<Member>>()
                         .as_slice(),
                     &mut self.query_queue,
-                    index,
+                    indexes,
                 );
 
                 for (idx, member) in t.iter().enumerate() {
@@ -628,25 +667,32 @@ impl Sql {
                         (entity_id, is_event_message),
                         member,
                         block_timestamp,
-                        index,
+                        indexes,
                     );
                 }
             }
             Ty::Array(array) => {
-                // delete all previous array elements
-                self.query_queue.enqueue(
-                    format!(
-                        "DELETE FROM [{table_id}] WHERE entity_id = ?",
-                        table_id = path.join("$")
-                    ),
-                    vec![Argument::String(entity_id.to_string())],
+                // delete all previous array elements, only within the parent array elements if
+                // the array is itself nested in arrays
+                let mut statement = format!(
+                    "DELETE FROM [{table_id}] WHERE entity_id = ?",
+                    table_id = path.join("$")
                 );
+                let mut arguments = vec![Argument::String(entity_id.to_string())];
+                for (column_idx, idx) in indexes.iter().enumerate() {
+                    statement.push_str(&format!(" AND idx_{} = ?", column_idx));
+                    arguments.push(Argument::Int(*idx));
+                }
+                self.query_queue.enqueue(statement, arguments);
 
                 for (idx, member) in array.iter().enumerate() {
+                    let mut member_indexes = indexes.to_vec();
+                    member_indexes.push(idx as i64);
+
                     update_members(
                         &[Member { name: "data".to_string(), ty: member.clone(), key: false }],
                         &mut self.query_queue,
-                        Some(idx as i64),
+                        &member_indexes,
                     );
 
                     let mut path_clone = path.clone();
@@ -657,7 +703,7 @@ impl Sql {
                         (entity_id, is_event_message),
                         member,
                         block_timestamp,
-                        Some(idx as i64),
+                        &member_indexes,
                     );
                 }
             }
@@ -704,8 +750,8 @@ impl Sql {
         model: &Ty,
         model_idx: i64,
         block_timestamp: u64,
-        is_array: bool,
-        is_parent_array: bool,
+        array_idx: usize,
+        parent_array_idx: usize,
     ) {
         let table_id = path.join("$");
         let mut indices = Vec::new();
@@ -715,8 +761,9 @@ impl Sql {
              entity_id TEXT, event_message_id TEXT, "
         );
 
-        if is_array {
-            create_table_query.push_str("idx INTEGER NOT NULL, ");
+        // one index column per array dimension
+        for column_idx in 0..array_idx {
+            create_table_query.push_str(&format!("idx_{} INTEGER NOT NULL, ", column_idx));
         }
 
         let mut build_member = |name: &str, ty: &Ty, options: &mut Option<Argument>| {
@@ -907,10 +954,11 @@ impl Sql {
         if path.len() > 1 {
             let parent_table_id = path[..path.len() - 1].join("$");
 
-            if is_parent_array && path.len() > 2 {
+            if parent_array_idx > 0 {
+                let parent_columns = index_columns(parent_array_idx);
                 create_table_query.push_str(&format!(
-                    "FOREIGN KEY (id, idx) REFERENCES {parent_table_id} (id, idx) ON DELETE \
-                     CASCADE, "
+                    "FOREIGN KEY ({parent_columns}) REFERENCES {parent_table_id} \
+                     ({parent_columns}) ON DELETE CASCADE, "
                 ));
             } else {
                 create_table_query.push_str(&format!(
@@ -920,11 +968,7 @@ impl Sql {
             }
         };
 
-        if is_array {
-            create_table_query.push_str("PRIMARY KEY (id, idx), ");
-        } else {
-            create_table_query.push_str("PRIMARY KEY (id), ");
-        }
+        create_table_query.push_str(&format!("PRIMARY KEY ({}), ", index_columns(array_idx)));
         create_table_query.push_str("FOREIGN KEY (entity_id) REFERENCES entities(id), ");
         // create_table_query.push_str("FOREIGN KEY (event_id) REFERENCES events(id), ");
         create_table_query
@@ -944,6 +988,14 @@ impl Sql {
     }
 }
 
+// The columns identifying a row of a table nested in `array_idx` arrays
+fn index_columns(array_idx: usize) -> String {
+    std::iter::once("id".to_string())
+        .chain((0..array_idx).map(|column_idx| format!("idx_{}", column_idx)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn felts_sql_string(felts: &[FieldElement]) -> String {
     felts.iter().map(|k| format!("{:#x}", k)).collect::<Vec<String>>().join(FELT_DELIMITER)
         + FELT_DELIMITER
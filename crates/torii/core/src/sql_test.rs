@@ -13,7 +13,7 @@ use scarb::ops;
 use sozo_ops::migration::execute_strategy;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use starknet::accounts::{Account, Call};
-use starknet::core::types::{BlockId, BlockTag};
+use starknet::core::types::{BlockId, BlockTag, FieldElement};
 use starknet::core::utils::get_selector_from_name;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, Provider};
@@ -172,3 +172,52 @@ async fn test_load_from_remote() {
 
     db.execute().await.unwrap();
 }
+
+#[tokio::test]
+async fn test_rename_legacy_array_index_columns() {
+    let options =
+        SqliteConnectOptions::from_str("sqlite::memory:").unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().max_connections(1).connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    // tables of a model with an array, as created before the support of nested arrays
+    sqlx::query("CREATE TABLE [Inventory] (id TEXT NOT NULL, PRIMARY KEY (id))")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "CREATE TABLE [Inventory$items] (id TEXT NOT NULL, idx INTEGER NOT NULL, PRIMARY KEY (id, \
+         idx), FOREIGN KEY (id) REFERENCES [Inventory] (id))",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "CREATE TABLE [Inventory$items$data] (id TEXT NOT NULL, idx INTEGER NOT NULL, PRIMARY KEY \
+         (id, idx), FOREIGN KEY (id, idx) REFERENCES [Inventory$items] (id, idx))",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    Sql::new(pool.clone(), FieldElement::ZERO).await.unwrap();
+
+    for table_id in ["Inventory$items", "Inventory$items$data"] {
+        let columns: Vec<(String,)> =
+            sqlx::query_as(&format!("SELECT name FROM pragma_table_info('{table_id}')"))
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(columns, vec![("id".to_string(),), ("idx_0".to_string(),)]);
+    }
+
+    sqlx::query("INSERT INTO [Inventory] (id) VALUES ('0x1')").execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO [Inventory$items] (id, idx_0) VALUES ('0x1', 0)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO [Inventory$items$data] (id, idx_0) VALUES ('0x1', 0)")
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -37,8 +37,17 @@ pub struct Entity {
     pub executed_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// The model of the entity which was set, scoping the broker channel the entity is published
+    /// to. It isn't stored along with the entity.
+    #[sqlx(default)]
+    #[serde(default)]
+    pub model: String,
 }
 
+/// An entity which had one of its models deleted, with its last known state.
+#[derive(Debug, Clone)]
+pub struct EntityDeleted(pub Entity);
+
 #[derive(FromRow, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct EventMessage {
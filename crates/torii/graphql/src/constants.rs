@@ -1,9 +1,14 @@
 pub const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
 
 pub const DEFAULT_LIMIT: u64 = 10;
+pub const MAX_LIMIT: u64 = 1000;
+pub const DEFAULT_LIST_BATCH_SIZE: u64 = 1000;
+pub const MAX_NESTING_DEPTH: usize = 32;
 pub const BOOLEAN_TRUE: i64 = 1;
 
 pub const ENTITY_TABLE: &str = "entities";
+pub const ENTITY_HISTORY_TABLE: &str = "entity_history";
+pub const ENTITY_MODEL_TABLE: &str = "entity_model";
 pub const EVENT_TABLE: &str = "events";
 pub const EVENT_MESSAGE_TABLE: &str = "event_messages";
 pub const MODEL_TABLE: &str = "models";
@@ -13,15 +18,23 @@ pub const METADATA_TABLE: &str = "metadata";
 pub const ID_COLUMN: &str = "id";
 pub const EVENT_ID_COLUMN: &str = "event_id";
 pub const ENTITY_ID_COLUMN: &str = "entity_id";
+pub const KEYS_COLUMN: &str = "keys";
 pub const JSON_COLUMN: &str = "json";
 pub const TRANSACTION_HASH_COLUMN: &str = "transaction_hash";
 
 pub const INTERNAL_ENTITY_ID_KEY: &str = "$entity_id$";
+pub const INTERNAL_RAW_KEYS_KEY: &str = "$raw_keys$";
+pub const INTERNAL_MODELS_DELTA_KEY: &str = "$models_delta$";
+
+pub const COMPRESSED_LIST_PREFIX: &str = "gzip+base64:";
 
 // objects namespaced to avoid conflicts with user models
 pub const ENTITY_TYPE_NAME: &str = "World__Entity";
+pub const ENTITY_AGGREGATE_TYPE_NAME: &str = "World__EntityAggregate";
+pub const ENTITY_HISTORY_TYPE_NAME: &str = "World__EntityHistory";
 pub const EVENT_MESSAGE_TYPE_NAME: &str = "World__EventMessage";
 pub const MODEL_TYPE_NAME: &str = "World__Model";
+pub const MODEL_FIELD_TYPE_NAME: &str = "World__ModelField";
 pub const EVENT_TYPE_NAME: &str = "World__Event";
 pub const SOCIAL_TYPE_NAME: &str = "World__Social";
 pub const CONTENT_TYPE_NAME: &str = "World__Content";
@@ -32,11 +45,18 @@ pub const QUERY_TYPE_NAME: &str = "World__Query";
 pub const SUBSCRIPTION_TYPE_NAME: &str = "World__Subscription";
 pub const MODEL_ORDER_TYPE_NAME: &str = "World__ModelOrder";
 pub const MODEL_ORDER_FIELD_TYPE_NAME: &str = "World__ModelOrderField";
+pub const MODEL_FIELD_KIND_TYPE_NAME: &str = "World__ModelFieldKind";
+pub const ENTITY_WHERE_INPUT_TYPE_NAME: &str = "World__EntityWhereInput";
+pub const ENTITY_ORDER_INPUT_TYPE_NAME: &str = "World__EntityOrder";
+pub const KEYS_RANGE_INPUT_TYPE_NAME: &str = "World__KeysRange";
 
 // objects' single and plural names
 pub const ENTITY_NAMES: (&str, &str) = ("entity", "entities");
+pub const ENTITY_AGGREGATE_NAMES: (&str, &str) = ("entitiesAggregate", "");
+pub const ENTITY_HISTORY_NAMES: (&str, &str) = ("eventHistory", "");
 pub const EVENT_MESSAGE_NAMES: (&str, &str) = ("eventMessage", "eventMessages");
 pub const MODEL_NAMES: (&str, &str) = ("model", "models");
+pub const MODEL_FIELD_NAMES: (&str, &str) = ("modelField", "modelFields");
 pub const EVENT_NAMES: (&str, &str) = ("event", "events");
 pub const SOCIAL_NAMES: (&str, &str) = ("social", "socials");
 pub const CONTENT_NAMES: (&str, &str) = ("content", "contents");
@@ -48,3 +68,7 @@ pub const PAGE_INFO_NAMES: (&str, &str) = ("pageInfo", "");
 pub const ORDER_DIR_TYPE_NAME: &str = "OrderDirection";
 pub const ORDER_ASC: &str = "ASC";
 pub const ORDER_DESC: &str = "DESC";
+pub const KEYS_FORMAT_TYPE_NAME: &str = "World__KeysFormat";
+pub const KEYS_FORMAT_STRING: &str = "STRING";
+pub const KEYS_FORMAT_HEX: &str = "HEX";
+pub const KEYS_FORMAT_DECIMAL: &str = "DECIMAL";
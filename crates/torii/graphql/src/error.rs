@@ -1,3 +1,4 @@
+use async_graphql::ErrorExtensions;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -12,3 +13,61 @@ pub enum ExtractError {
     #[error("Not a number: {0}")]
     NotNumber(String),
 }
+
+/// Errors of the resolvers of the GraphQL objects, exposed to the clients with a stable `code`
+/// extension so they can be handled without matching on the messages.
+#[derive(Debug, Error)]
+pub enum GraphqlObjectError {
+    #[error("incorrect value, requires Value::{0}")]
+    UnexpectedValue(&'static str),
+    #[error("Not found: {0}")]
+    MissingField(String),
+    #[error(transparent)]
+    InvalidField(ExtractError),
+    #[error(
+        "The data of the model '{0}' could not be found, the world may need to be re-indexed."
+    )]
+    TableNotFound(String),
+    #[error(
+        "The event history of the entities is not indexed, the database may need to be migrated."
+    )]
+    HistoryNotFound,
+    #[error("The data of '{0}' does not match the type mapping of its model.")]
+    MalformedData(String),
+    #[error("The type of the model '{0}' is nested deeper than the maximum depth of {1}.")]
+    NestingTooDeep(String, usize),
+    #[error(transparent)]
+    Sql(#[from] sqlx::Error),
+}
+
+impl GraphqlObjectError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            GraphqlObjectError::UnexpectedValue(_) => "UNEXPECTED_VALUE",
+            GraphqlObjectError::MissingField(_) => "MISSING_FIELD",
+            GraphqlObjectError::InvalidField(_) => "INVALID_FIELD",
+            GraphqlObjectError::TableNotFound(_) | GraphqlObjectError::HistoryNotFound => {
+                "TABLE_NOT_FOUND"
+            }
+            GraphqlObjectError::MalformedData(_) => "MALFORMED_DATA",
+            GraphqlObjectError::NestingTooDeep(..) => "NESTING_TOO_DEEP",
+            GraphqlObjectError::Sql(_) => "DATABASE_ERROR",
+        }
+    }
+}
+
+impl From<ExtractError> for GraphqlObjectError {
+    fn from(error: ExtractError) -> Self {
+        match error {
+            ExtractError::NotFound(field) => GraphqlObjectError::MissingField(field),
+            error => GraphqlObjectError::InvalidField(error),
+        }
+    }
+}
+
+impl ErrorExtensions for GraphqlObjectError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string())
+            .extend_with(|_, extensions| extensions.set("code", self.code()))
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextExecute, NextPrepareRequest,
+};
+use async_graphql::{Request, Response, ServerResult, Value};
+use tracing::{info_span, Instrument};
+
+pub(crate) const LOG_TARGET: &str = "torii_graphql::request";
+
+// The ids of the requests are unique across the schemas, which are rebuilt as models are registered
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+// Collects the warnings raised by the resolvers of a request, and sends them back in the
+// `warnings` extension of the response. Unlike errors, the data is still fully resolved
+pub struct Warnings;
+
+impl ExtensionFactory for Warnings {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(WarningsExtension::default())
+    }
+}
+
+// Request data through which the resolvers raise their warnings
+#[derive(Debug, Clone, Default)]
+pub struct RequestWarnings(Arc<Mutex<Vec<String>>>);
+
+impl RequestWarnings {
+    pub fn push(&self, warning: String) {
+        self.0.lock().unwrap().push(warning);
+    }
+}
+
+#[derive(Default)]
+struct WarningsExtension {
+    warnings: RequestWarnings,
+}
+
+#[async_trait::async_trait]
+impl Extension for WarningsExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        next.run(ctx, request.data(self.warnings.clone())).await
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let mut response = next.run(ctx, operation_name).await;
+
+        let warnings = std::mem::take(&mut *self.warnings.0.lock().unwrap());
+        if !warnings.is_empty() {
+            response.extensions.insert(
+                "warnings".to_string(),
+                Value::List(warnings.into_iter().map(Value::from).collect()),
+            );
+        }
+
+        response
+    }
+}
+
+// Executes each request in a span carrying a unique request id, which the spans and the logs of
+// its resolvers inherit (eg the ones of the queries fetching the model data of an entity), so that
+// they can be grouped by request
+pub struct RequestSpans;
+
+impl ExtensionFactory for RequestSpans {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(RequestSpansExtension {
+            request_id: NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed),
+        })
+    }
+}
+
+struct RequestSpansExtension {
+    request_id: u64,
+}
+
+#[async_trait::async_trait]
+impl Extension for RequestSpansExtension {
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let span = info_span!(
+            target: LOG_TARGET,
+            "request",
+            request_id = self.request_id,
+            operation = operation_name.unwrap_or_default()
+        );
+
+        next.run(ctx, operation_name).instrument(span).await
+    }
+}
@@ -2,6 +2,7 @@ pub mod object;
 
 mod constants;
 mod error;
+mod extension;
 mod mapping;
 mod query;
 pub mod schema;
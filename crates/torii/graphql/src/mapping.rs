@@ -4,7 +4,7 @@ use async_graphql::Name;
 use dojo_types::primitive::Primitive;
 use lazy_static::lazy_static;
 
-use crate::constants::{CONTENT_TYPE_NAME, SOCIAL_TYPE_NAME};
+use crate::constants::{CONTENT_TYPE_NAME, MODEL_FIELD_KIND_TYPE_NAME, SOCIAL_TYPE_NAME};
 use crate::types::{GraphqlType, TypeData, TypeMapping};
 
 lazy_static! {
@@ -25,6 +25,20 @@ lazy_static! {
             TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
         ),
     ]);
+    pub static ref ENTITY_AGGREGATE_TYPE_MAPPING: TypeMapping = IndexMap::from([
+        (Name::new("count"), TypeData::Simple(TypeRef::named_nn(TypeRef::INT))),
+        (Name::new("min"), TypeData::Simple(TypeRef::named(TypeRef::INT))),
+        (Name::new("max"), TypeData::Simple(TypeRef::named(TypeRef::INT))),
+        (Name::new("sum"), TypeData::Simple(TypeRef::named(TypeRef::INT))),
+        (Name::new("avg"), TypeData::Simple(TypeRef::named(TypeRef::FLOAT))),
+    ]);
+    pub static ref ENTITY_HISTORY_TYPE_MAPPING: TypeMapping = IndexMap::from([
+        (Name::new("eventId"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (
+            Name::new("executedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+    ]);
     pub static ref EVENT_TYPE_MAPPING: TypeMapping = IndexMap::from([
         (Name::new("id"), TypeData::Simple(TypeRef::named(TypeRef::ID))),
         (Name::new("keys"), TypeData::Simple(TypeRef::named_list(TypeRef::STRING))),
@@ -63,6 +77,11 @@ lazy_static! {
             TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
         ),
     ]);
+    pub static ref MODEL_FIELD_TYPE_MAPPING: TypeMapping = IndexMap::from([
+        (Name::new("name"), TypeData::Simple(TypeRef::named_nn(TypeRef::STRING))),
+        (Name::new("type"), TypeData::Simple(TypeRef::named_nn(TypeRef::STRING))),
+        (Name::new("kind"), TypeData::Simple(TypeRef::named_nn(MODEL_FIELD_KIND_TYPE_NAME))),
+    ]);
     pub static ref TRANSACTION_MAPPING: TypeMapping = IndexMap::from([
         (Name::new("id"), TypeData::Simple(TypeRef::named(TypeRef::ID))),
         (
@@ -2,22 +2,48 @@ use async_graphql::Error;
 use base64::engine::general_purpose;
 use base64::Engine as _;
 
+// Version of the cursor payloads. Cursors are made of the logical keys of the rows (ie their entity
+// or event id and the value of the ordering field) rather than of their rowids, so that they remain
+// valid when the database is reindexed. The version is to be bumped whenever the payload changes,
+// cursors of another version being rejected rather than misread
+pub const CURSOR_VERSION: u8 = 1;
+
 pub fn encode(primary: &str, secondary: &str) -> String {
-    let cursor = format!("cursor/{}/{}", primary, secondary);
+    let cursor = format!("cursor/v{}/{}/{}", CURSOR_VERSION, primary, secondary);
     general_purpose::STANDARD.encode(cursor.as_bytes())
 }
 
 pub fn decode(cursor: &str) -> Result<(String, String), Error> {
     let bytes = general_purpose::STANDARD.decode(cursor)?;
     let cursor = String::from_utf8(bytes)?;
-    let parts: Vec<&str> = cursor.split('/').collect();
+    // the value of the ordering field may contain slashes, it takes the rest of the payload
+    let parts: Vec<&str> = cursor.splitn(4, '/').collect();
 
-    if parts.len() != 3 || parts[0] != "cursor" {
+    if parts.len() < 3 || parts[0] != "cursor" {
         return Err("Invalid cursor format".into());
     }
 
-    let primary = parts[1].parse::<String>()?;
-    let secondary = parts[2].parse::<String>()?;
+    match parts[1].strip_prefix('v').and_then(|version| version.parse::<u8>().ok()) {
+        Some(CURSOR_VERSION) => {}
+        Some(version) => {
+            return Err(format!(
+                "Cursor of version {} is not supported, the current version is {}",
+                version, CURSOR_VERSION
+            )
+            .into());
+        }
+        None => {
+            return Err(format!(
+                "Unversioned cursor is not supported, the current version is {}",
+                CURSOR_VERSION
+            )
+            .into());
+        }
+    }
+
+    if parts.len() != 4 {
+        return Err("Invalid cursor format".into());
+    }
 
-    Ok((primary, secondary))
+    Ok((parts[2].to_string(), parts[3].to_string()))
 }
@@ -7,10 +7,11 @@ use sqlx::Row;
 
 use self::page_info::PageInfoObject;
 use super::BasicObject;
-use crate::constants::PAGE_INFO_TYPE_NAME;
+use crate::constants::{DEFAULT_LIMIT, PAGE_INFO_TYPE_NAME};
+use crate::extension::RequestWarnings;
 use crate::query::order::Order;
 use crate::query::value_mapping_from_row;
-use crate::types::{GraphqlType, TypeData, TypeMapping, ValueMapping};
+use crate::types::{GraphqlType, RowCap, TypeData, TypeMapping, ValueMapping};
 use crate::utils::extract;
 
 pub mod cursor;
@@ -70,12 +71,12 @@ impl BasicObject for ConnectionObject {
 }
 
 pub fn parse_connection_arguments(ctx: &ResolverContext<'_>) -> Result<ConnectionArguments, Error> {
-    let first = extract::<u64>(ctx.args.as_index_map(), "first").ok();
-    let last = extract::<u64>(ctx.args.as_index_map(), "last").ok();
+    let mut first = extract::<u64>(ctx.args.as_index_map(), "first").ok();
+    let mut last = extract::<u64>(ctx.args.as_index_map(), "last").ok();
     let after = extract::<String>(ctx.args.as_index_map(), "after").ok();
     let before = extract::<String>(ctx.args.as_index_map(), "before").ok();
     let offset = extract::<u64>(ctx.args.as_index_map(), "offset").ok();
-    let limit = extract::<u64>(ctx.args.as_index_map(), "limit").ok();
+    let mut limit = extract::<u64>(ctx.args.as_index_map(), "limit").ok();
 
     if first.is_some() && last.is_some() {
         return Err(
@@ -97,6 +98,25 @@ pub fn parse_connection_arguments(ctx: &ResolverContext<'_>) -> Result<Connectio
             .into());
     }
 
+    // the rows fetched are capped whatever the arguments, the client being warned when the
+    // connection is truncated rather than the query being rejected
+    let RowCap(row_cap) = ctx.data_opt::<RowCap>().copied().unwrap_or_default();
+    if first.or(last).or(limit).unwrap_or(DEFAULT_LIMIT) > row_cap {
+        if let Some(warnings) = ctx.data_opt::<RequestWarnings>() {
+            warnings.push(format!(
+                "The `{}` connection was truncated to {} edges, the maximum allowed by the server.",
+                ctx.field().name(),
+                row_cap
+            ));
+        }
+
+        first = first.map(|_| row_cap);
+        last = last.map(|_| row_cap);
+        if first.is_none() && last.is_none() {
+            limit = Some(row_cap);
+        }
+    }
+
     Ok(ConnectionArguments { first, last, after, before, offset, limit })
 }
 
@@ -1,30 +1,63 @@
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
 use async_graphql::dynamic::indexmap::IndexMap;
 use async_graphql::dynamic::{
-    Field, FieldFuture, FieldValue, InputValue, SubscriptionField, SubscriptionFieldFuture, TypeRef,
+    Enum, Field, FieldFuture, FieldValue, InputObject, InputValue, SubscriptionField,
+    SubscriptionFieldFuture, TypeRef,
 };
-use async_graphql::{Name, Value};
+use async_graphql::{ErrorExtensions, Name, SelectionField, Value};
 use async_recursion::async_recursion;
+use dojo_types::schema::Ty;
 use sqlx::pool::PoolConnection;
-use sqlx::{Pool, Sqlite};
-use tokio_stream::StreamExt;
+use sqlx::{Pool, Row, Sqlite};
+use starknet::core::utils::get_selector_from_name;
+use tokio_stream::{Stream, StreamExt};
+use torii_core::cache::ModelCache;
+use torii_core::model::{build_sql_query, map_row_to_ty};
 use torii_core::simple_broker::SimpleBroker;
-use torii_core::types::Entity;
+use torii_core::sql::FELT_DELIMITER;
+use torii_core::types::{Entity, EntityDeleted};
+use tracing::{debug_span, error, trace, Instrument};
 
-use super::inputs::keys_input::keys_argument;
+use super::entity_aggregate::entity_aggregate_field;
+use super::entity_history::entity_history_field;
+use super::inputs::at_block_input::at_block_argument;
+use super::inputs::entity_order_input::{entity_order_argument, entity_order_input_object};
+use super::inputs::entity_where_input::{entity_where_argument, entity_where_input_object};
+use super::inputs::keys_input::{
+    keys_argument, keys_range_argument, keys_range_input_object, parse_keys_argument,
+};
+use super::model_data::ComputedFields;
 use super::{BasicObject, ResolvableObject, TypeMapping, ValueMapping};
 use crate::constants::{
-    DATETIME_FORMAT, ENTITY_NAMES, ENTITY_TABLE, ENTITY_TYPE_NAME, EVENT_ID_COLUMN, ID_COLUMN,
+    ENTITY_ID_COLUMN, ENTITY_TABLE, ENTITY_TYPE_NAME, EVENT_ID_COLUMN, ID_COLUMN,
+    INTERNAL_MODELS_DELTA_KEY, INTERNAL_RAW_KEYS_KEY, KEYS_FORMAT_DECIMAL, KEYS_FORMAT_HEX,
+    KEYS_FORMAT_STRING, KEYS_FORMAT_TYPE_NAME, MAX_NESTING_DEPTH, ORDER_DIR_TYPE_NAME,
 };
+use crate::error::GraphqlObjectError;
 use crate::mapping::ENTITY_TYPE_MAPPING;
-use crate::object::{resolve_many, resolve_one};
-use crate::query::{type_mapping_query, value_mapping_from_row};
-use crate::types::TypeData;
+use crate::object::{resolve_by_ids, resolve_many, resolve_one};
+use crate::query::data::fetch_entities_with_models;
+use crate::query::order::Direction;
+use crate::query::{
+    option_type_mapping, option_value, project_type_mapping, split_felts, type_mapping_query_many,
+    validate_identifier, value_mapping_from_row, TypeMappingCache,
+};
+use crate::types::{DatetimeFormat, EntityNames, FieldCasing, ListBatchSize, RowCap, TypeData};
 use crate::utils::extract;
-pub struct EntityObject;
+
+pub(crate) const LOG_TARGET: &str = "torii_graphql::object::entity";
+
+#[derive(Default)]
+pub struct EntityObject {
+    names: EntityNames,
+}
 
 impl BasicObject for EntityObject {
     fn name(&self) -> (&str, &str) {
-        ENTITY_NAMES
+        (self.names.one(), self.names.many())
     }
 
     fn type_name(&self) -> &str {
@@ -36,19 +69,42 @@ impl BasicObject for EntityObject {
     }
 
     fn related_fields(&self) -> Option<Vec<Field>> {
-        Some(vec![model_union_field()])
+        Some(vec![
+            model_union_field(),
+            model_names_field(),
+            raw_keys_field(),
+            raw_model_field(),
+            entity_history_field(),
+        ])
     }
 }
 
 impl ResolvableObject for EntityObject {
+    // the formats of the keys of all the objects having keys
+    fn enum_objects(&self) -> Option<Vec<Enum>> {
+        Some(vec![Enum::new(KEYS_FORMAT_TYPE_NAME)
+            .item(KEYS_FORMAT_STRING)
+            .item(KEYS_FORMAT_HEX)
+            .item(KEYS_FORMAT_DECIMAL)])
+    }
+
+    fn input_objects(&self) -> Option<Vec<InputObject>> {
+        Some(vec![
+            entity_where_input_object(),
+            entity_order_input_object(),
+            keys_range_input_object(),
+        ])
+    }
+
     fn resolvers(&self) -> Vec<Field> {
-        let resolve_one = resolve_one(
+        let mut resolve_one = resolve_one(
             ENTITY_TABLE,
             ID_COLUMN,
             self.name().0,
             self.type_name(),
             self.type_mapping(),
         );
+        resolve_one = at_block_argument(resolve_one);
 
         let mut resolve_many = resolve_many(
             ENTITY_TABLE,
@@ -58,8 +114,27 @@ impl ResolvableObject for EntityObject {
             self.type_mapping(),
         );
         resolve_many = keys_argument(resolve_many);
+        resolve_many = keys_range_argument(resolve_many);
+        resolve_many = entity_where_argument(resolve_many);
+        resolve_many = entity_order_argument(resolve_many);
+        resolve_many = at_block_argument(resolve_many);
+
+        let mut resolve_by_ids = resolve_by_ids(
+            ENTITY_TABLE,
+            ID_COLUMN,
+            &format!("{}ByIds", self.name().1),
+            self.type_name(),
+            self.type_mapping(),
+        );
+        resolve_by_ids = at_block_argument(resolve_by_ids);
 
-        vec![resolve_one, resolve_many]
+        vec![
+            resolve_one,
+            resolve_many,
+            resolve_by_ids,
+            entities_with_models_field(&format!("{}WithModels", self.name().1)),
+            entity_aggregate_field(),
+        ]
     }
 
     fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
@@ -70,16 +145,88 @@ impl ResolvableObject for EntityObject {
                         Some(id) => Some(id.string()?.to_string()),
                         None => None,
                     };
+                    let keys = parse_keys_argument(&ctx)?;
+                    let datetime_format = *ctx.data::<DatetimeFormat>()?;
+                    let delta = match ctx.args.get("delta") {
+                        Some(delta) => delta.boolean()?,
+                        None => false,
+                    };
+                    let pool = ctx.data::<Pool<Sqlite>>()?.clone();
+                    let list_batch_size =
+                        ctx.data_opt::<ListBatchSize>().copied().unwrap_or_default();
+                    // the model data of the entities as last sent, to compute the deltas from
+                    let states = Arc::new(EntityModelsStates::default());
+                    // if model is Some, then the subscription is only woken up by the entities
+                    // set with that model
+                    let entities: Pin<Box<dyn Stream<Item = Entity> + Send>> =
+                        match ctx.args.get("model") {
+                            Some(model) => {
+                                Box::pin(SimpleBroker::<Entity>::subscribe_scoped(model.string()?))
+                            }
+                            None => Box::pin(SimpleBroker::<Entity>::subscribe()),
+                        };
                     // if id is None, then subscribe to all entities
                     // if id is Some, then subscribe to only the entity with that id
-                    Ok(SimpleBroker::<Entity>::subscribe().filter_map(move |entity: Entity| {
-                        if id.is_none() || id == Some(entity.id.clone()) {
-                            Some(Ok(Value::Object(EntityObject::value_mapping(entity))))
-                        } else {
-                            // id != entity.id , then don't send anything, still listening
-                            None
-                        }
-                    }))
+                    // if keys is Some, then only the entities matching the keys are sent
+                    // if delta is true, then the models only hold the fields which changed
+                    Ok(entities
+                        .filter(move |entity: &Entity| {
+                            (id.is_none() || id.as_ref() == Some(&entity.id))
+                                && keys
+                                    .as_ref()
+                                    .map_or(true, |keys| EntityObject::match_keys(keys, entity))
+                        })
+                        .then(move |entity: Entity| {
+                            let pool = pool.clone();
+                            let states = states.clone();
+
+                            async move {
+                                let mut value_mapping =
+                                    EntityObject::value_mapping(entity, datetime_format);
+                                if delta {
+                                    let models = entity_models_delta(
+                                        &pool,
+                                        &value_mapping,
+                                        &states,
+                                        list_batch_size,
+                                    )
+                                    .await
+                                    .map_err(|error| error.extend())?;
+                                    value_mapping
+                                        .insert(Name::new(INTERNAL_MODELS_DELTA_KEY), models);
+                                }
+
+                                Ok::<_, async_graphql::Error>(Value::Object(value_mapping))
+                            }
+                        }))
+                })
+            })
+            .argument(InputValue::new("id", TypeRef::named(TypeRef::ID)))
+            .argument(InputValue::new("keys", TypeRef::named_list(TypeRef::STRING)))
+            .argument(InputValue::new("model", TypeRef::named(TypeRef::STRING)))
+            .argument(InputValue::new("delta", TypeRef::named(TypeRef::BOOLEAN))),
+            SubscriptionField::new("entityDeleted", TypeRef::named_nn(self.type_name()), |ctx| {
+                SubscriptionFieldFuture::new(async move {
+                    let id = match ctx.args.get("id") {
+                        Some(id) => Some(id.string()?.to_string()),
+                        None => None,
+                    };
+                    let datetime_format = *ctx.data::<DatetimeFormat>()?;
+                    // if id is None, then subscribe to all deleted entities
+                    // if id is Some, then subscribe to only the entity with that id
+                    Ok(SimpleBroker::<EntityDeleted>::subscribe().filter_map(
+                        move |EntityDeleted(entity)| {
+                            if id.is_none() || id == Some(entity.id.clone()) {
+                                Some(Ok(Value::Object(EntityObject::value_mapping(
+                                    entity,
+                                    datetime_format,
+                                ))))
+                            } else {
+                                // id != entity.id , then don't send anything, still listening
+                                None
+                            }
+                        },
+                    ))
                 })
             })
             .argument(InputValue::new("id", TypeRef::named(TypeRef::ID))),
@@ -88,74 +235,83 @@ impl ResolvableObject for EntityObject {
 }
 
 impl EntityObject {
-    pub fn value_mapping(entity: Entity) -> ValueMapping {
-        let keys: Vec<&str> = entity.keys.split('/').filter(|&k| !k.is_empty()).collect();
+    pub fn new(names: EntityNames) -> Self {
+        Self { names }
+    }
+
+    pub fn value_mapping(entity: Entity, datetime_format: DatetimeFormat) -> ValueMapping {
         IndexMap::from([
             (Name::new("id"), Value::from(entity.id)),
-            (Name::new("keys"), Value::from(keys)),
+            (Name::new("keys"), Value::from(split_felts(&entity.keys))),
+            (Name::new(INTERNAL_RAW_KEYS_KEY), Value::from(entity.keys)),
             (Name::new("eventId"), Value::from(entity.event_id)),
-            (
-                Name::new("createdAt"),
-                Value::from(entity.created_at.format(DATETIME_FORMAT).to_string()),
-            ),
-            (
-                Name::new("updatedAt"),
-                Value::from(entity.updated_at.format(DATETIME_FORMAT).to_string()),
-            ),
-            (
-                Name::new("executedAt"),
-                Value::from(entity.executed_at.format(DATETIME_FORMAT).to_string()),
-            ),
+            (Name::new("createdAt"), Value::from(datetime_format.format(&entity.created_at))),
+            (Name::new("updatedAt"), Value::from(datetime_format.format(&entity.updated_at))),
+            (Name::new("executedAt"), Value::from(datetime_format.format(&entity.executed_at))),
         ])
     }
+
+    // Checks if the provided keys match the entity's keys, allowing '*' as a wildcard. Like the
+    // `keys` argument of the entities query, the provided keys are a prefix of the entity's keys.
+    pub fn match_keys(input_keys: &[String], entity: &Entity) -> bool {
+        let entity_keys: Vec<&str> =
+            entity.keys.split(FELT_DELIMITER).filter(|s| !s.is_empty()).collect();
+
+        if input_keys.len() > entity_keys.len() {
+            return false;
+        }
+
+        input_keys
+            .iter()
+            .zip(entity_keys.iter())
+            .all(|(input_key, entity_key)| input_key == "*" || input_key == entity_key)
+    }
 }
 
-fn model_union_field() -> Field {
-    Field::new("models", TypeRef::named_list("ModelUnion"), move |ctx| {
+// The entities having all the given models, as an archetype query of an ECS. The entities are
+// capped to the row cap of the connections
+fn entities_with_models_field(field_name: &str) -> Field {
+    Field::new(field_name, TypeRef::named_nn_list_nn(ENTITY_TYPE_NAME), |ctx| {
         FieldFuture::new(async move {
-            match ctx.parent_value.try_to_value()? {
-                Value::Object(indexmap) => {
-                    let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
-
-                    let entity_id = extract::<String>(indexmap, "id")?;
-                    // fetch name from the models table
-                    // using the model id (hashed model name)
-                    let model_ids: Vec<(String, String)> = sqlx::query_as(
-                        "SELECT id, name
-                        FROM models
-                        WHERE id IN (    
-                            SELECT model_id
-                            FROM entity_model
-                            WHERE entity_id = ?
-                        )",
-                    )
-                    .bind(&entity_id)
-                    .fetch_all(&mut *conn)
-                    .await?;
-
-                    let mut results: Vec<FieldValue<'_>> = Vec::new();
-                    for (id, name) in model_ids {
-                        // the model id in the model mmeebrs table is the hashed model name (id)
-                        let type_mapping = type_mapping_query(&mut conn, &id).await?;
-
-                        // but the table name for the model data is the unhashed model name
-                        let data: ValueMapping = match model_data_recursive_query(
-                            &mut conn,
-                            vec![name.clone()],
-                            &entity_id,
-                            None,
-                            &type_mapping,
-                        )
-                        .await?
-                        {
-                            Value::Object(map) => map,
-                            _ => unreachable!(),
-                        };
+            let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+            let RowCap(row_cap) = ctx.data_opt::<RowCap>().copied().unwrap_or_default();
+
+            let mut models: Vec<String> = vec![];
+            for model in extract::<Vec<String>>(ctx.args.as_index_map(), "all")? {
+                if validate_identifier(&model).is_err() {
+                    return Err(format!("'{}' is not a valid model name.", model).into());
+                }
+                if !models.contains(&model) {
+                    models.push(model);
+                }
+            }
+
+            if models.is_empty() {
+                return Err("At least one model is required.".into());
+            }
+
+            let data = fetch_entities_with_models(&mut conn, &models, row_cap).await?;
+            let entities = data
+                .iter()
+                .map(|row| {
+                    value_mapping_from_row(row, &ENTITY_TYPE_MAPPING, false).map(Value::Object)
+                })
+                .collect::<sqlx::Result<Vec<_>>>()?;
 
-                        results.push(FieldValue::with_type(FieldValue::owned_any(data), name));
-                    }
+            Ok(Some(Value::List(entities)))
+        })
+    })
+    .argument(InputValue::new("all", TypeRef::named_nn_list_nn(TypeRef::STRING)))
+}
 
-                    Ok(Some(FieldValue::list(results)))
+// The keys of the entity as stored, without being split
+fn raw_keys_field() -> Field {
+    Field::new("rawKeys", TypeRef::named_nn(TypeRef::STRING), move |ctx| {
+        FieldFuture::new(async move {
+            match ctx.parent_value.try_to_value()? {
+                Value::Object(indexmap) => {
+                    let raw_keys = extract::<String>(indexmap, INTERNAL_RAW_KEYS_KEY)?;
+                    Ok(Some(Value::from(raw_keys)))
                 }
                 _ => Err("incorrect value, requires Value::Object".into()),
             }
@@ -163,87 +319,676 @@ fn model_union_field() -> Field {
     })
 }
 
-// TODO: flatten query
-#[async_recursion]
+fn model_union_field() -> Field {
+    Field::new("models", TypeRef::named_list("ModelUnion"), move |ctx| {
+        FieldFuture::new(async move {
+            let Value::Object(indexmap) = ctx.parent_value.try_to_value()? else {
+                return Err(GraphqlObjectError::UnexpectedValue("Object").extend());
+            };
+
+            // the entities updated as deltas come with the changed data of their models
+            if let Some(Value::Object(delta)) = indexmap.get(INTERNAL_MODELS_DELTA_KEY) {
+                return Ok(Some(FieldValue::list(delta.iter().map(|(name, data)| {
+                    let data = match data {
+                        Value::Object(data) => data.clone(),
+                        _ => ValueMapping::new(),
+                    };
+                    FieldValue::with_type(FieldValue::owned_any(data), name.to_string())
+                }))));
+            }
+
+            let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+            let cache = ctx.data_opt::<TypeMappingCache>();
+            let computed_fields = ctx.data_opt::<ComputedFields>();
+            let field_casing = ctx.data_opt::<FieldCasing>().copied().unwrap_or_default();
+            let list_batch_size = ctx.data_opt::<ListBatchSize>().copied().unwrap_or_default();
+
+            let (models, errors) = entity_models(
+                &mut conn,
+                indexmap,
+                ctx.args.as_index_map(),
+                Some(ctx.field()),
+                field_casing,
+                computed_fields,
+                cache,
+                list_batch_size,
+            )
+            .await
+            .map_err(|error| error.extend())?;
+
+            // in partial mode, the models which failed are reported along with the other ones
+            for error in errors {
+                ctx.add_error(ctx.set_error_path(error.extend().into_server_error(ctx.item.pos)));
+            }
+
+            Ok(Some(FieldValue::list(
+                models
+                    .into_iter()
+                    .map(|(name, data)| FieldValue::with_type(FieldValue::owned_any(data), name)),
+            )))
+        })
+    })
+    .argument(InputValue::new("names", TypeRef::named_nn_list(TypeRef::STRING)))
+    .argument(InputValue::new("limit", TypeRef::named(TypeRef::INT)))
+    .argument(InputValue::new("offset", TypeRef::named(TypeRef::INT)))
+    .argument(InputValue::new("orderBy", TypeRef::named(ORDER_DIR_TYPE_NAME)))
+    .argument(InputValue::new("partial", TypeRef::named(TypeRef::BOOLEAN)))
+}
+
+// The names of the models of the entity, without fetching their data
+fn model_names_field() -> Field {
+    Field::new("modelNames", TypeRef::named_nn_list(TypeRef::STRING), move |ctx| {
+        FieldFuture::new(async move {
+            let Value::Object(indexmap) = ctx.parent_value.try_to_value()? else {
+                return Err(GraphqlObjectError::UnexpectedValue("Object").extend());
+            };
+            let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+
+            let entity_id = extract::<String>(indexmap, "id")?;
+            let names: Vec<String> = sqlx::query_scalar(
+                "SELECT name
+                FROM models
+                WHERE id IN (
+                    SELECT model_id
+                    FROM entity_model
+                    WHERE entity_id = ?
+                )
+                ORDER BY name",
+            )
+            .bind(&entity_id)
+            .fetch_all(&mut *conn)
+            .await?;
+
+            Ok(Some(Value::List(names.into_iter().map(Value::from).collect())))
+        })
+    })
+}
+
+// The values of a model of the entity serialized as felts, as they are stored onchain, rather than
+// decoded. Null if the entity doesn't have the model
+fn raw_model_field() -> Field {
+    Field::new("rawModel", TypeRef::named_nn_list(TypeRef::STRING), move |ctx| {
+        FieldFuture::new(async move {
+            let Value::Object(indexmap) = ctx.parent_value.try_to_value()? else {
+                return Err(GraphqlObjectError::UnexpectedValue("Object").extend());
+            };
+            let pool = ctx.data::<Pool<Sqlite>>()?;
+
+            let entity_id = extract::<String>(indexmap, "id")?;
+            let name = ctx.args.try_get("name")?.string()?;
+            let model_id = format!("{:#x}", get_selector_from_name(name)?);
+
+            let schema = ModelCache::new(pool.clone()).schema(&model_id).await?;
+            if !is_serializable(&schema) {
+                return Err(format!(
+                    "The model '{}' can't be serialized back, only its primitives, structs and \
+                     enums without data are supported.",
+                    name
+                )
+                .into());
+            }
+
+            let query = format!(
+                "{} WHERE {}.{} = ?",
+                build_sql_query(&vec![schema.clone()], ENTITY_TABLE, ENTITY_ID_COLUMN)?,
+                ENTITY_TABLE,
+                ID_COLUMN
+            );
+            let Some(row) = sqlx::query(&query).bind(&entity_id).fetch_optional(pool).await? else {
+                return Ok(None);
+            };
+
+            let mut struct_ty = schema.as_struct().expect("schema should be struct").to_owned();
+            map_row_to_ty(&schema.name(), &mut struct_ty, &row)?;
+            let felts = Ty::Struct(struct_ty).serialize()?;
+
+            Ok(Some(Value::List(
+                felts.into_iter().map(|felt| Value::from(format!("{:#x}", felt))).collect(),
+            )))
+        })
+    })
+    .argument(InputValue::new("name", TypeRef::named_nn(TypeRef::STRING)))
+}
+
+// The values read back from the model tables only cover the primitives, the structs and the
+// options of the enums
+fn is_serializable(ty: &Ty) -> bool {
+    match ty {
+        Ty::Primitive(_) => true,
+        Ty::Struct(struct_ty) => {
+            struct_ty.children.iter().all(|member| is_serializable(&member.ty))
+        }
+        Ty::Enum(enum_ty) => enum_ty
+            .options
+            .iter()
+            .all(|option| matches!(&option.ty, Ty::Tuple(types) if types.is_empty())),
+        Ty::Tuple(_) | Ty::Array(_) | Ty::ByteArray(_) => false,
+    }
+}
+
+// Fetches the data of the models of an entity, along with their names, according to the
+// arguments of the `models` field. Only the fields in its selection set are fetched, if any. In
+// partial mode, the errors of the models whose data can't be fetched are returned along with the
+// other models, rather than failing the whole list. The rows of the lists are fetched by batches of
+// `list_batch_size` rows
+#[allow(clippy::too_many_arguments)]
+async fn entity_models(
+    conn: &mut PoolConnection<Sqlite>,
+    entity: &ValueMapping,
+    args: &ValueMapping,
+    selection: Option<SelectionField<'_>>,
+    field_casing: FieldCasing,
+    computed_fields: Option<&ComputedFields>,
+    cache: Option<&TypeMappingCache>,
+    list_batch_size: ListBatchSize,
+) -> Result<(Vec<(String, ValueMapping)>, Vec<GraphqlObjectError>), GraphqlObjectError> {
+    let entity_id = extract::<String>(entity, "id")?;
+    let partial = matches!(args.get("partial"), Some(Value::Boolean(true)));
+    let names = extract::<Vec<String>>(args, "names").ok();
+    let limit = extract::<u64>(args, "limit").ok();
+    let offset = extract::<u64>(args, "offset").ok();
+    let order_by = match args.get("orderBy") {
+        Some(Value::Enum(name)) => Direction::try_from(name.as_str()).ok(),
+        _ => None,
+    };
+
+    // fetch name from the models table
+    // using the model id (hashed model name)
+    let mut query = "SELECT id, name
+        FROM models
+        WHERE id IN (
+            SELECT model_id
+            FROM entity_model
+            WHERE entity_id = ?
+        )"
+    .to_string();
+    if let Some(names) = &names {
+        let placeholders = vec!["?"; names.len()].join(", ");
+        query.push_str(&format!(" AND name IN ({})", placeholders));
+    }
+
+    // models are ordered by id unless ordered by name, so pages are deterministic
+    match &order_by {
+        Some(direction) => query.push_str(&format!(" ORDER BY name {}", direction.as_ref())),
+        None => query.push_str(" ORDER BY id"),
+    }
+
+    // sqlite requires a limit for the offset, -1 meaning no limit
+    if limit.is_some() || offset.is_some() {
+        query.push_str(" LIMIT ? OFFSET ?");
+    }
+
+    let mut query = sqlx::query_as(&query).bind(&entity_id);
+    for name in names.iter().flatten() {
+        query = query.bind(name);
+    }
+    if limit.is_some() || offset.is_some() {
+        query = query.bind(limit.map_or(-1, |limit| limit as i64)).bind(offset.unwrap_or(0) as i64);
+    }
+    let model_ids: Vec<(String, String)> = query.fetch_all(conn.as_mut()).await?;
+
+    // the model id in the model mmeebrs table is the hashed model name (id)
+    let ids: Vec<String> = model_ids.iter().map(|(id, _)| id.clone()).collect();
+    let type_mappings = match cache {
+        Some(cache) => cache.type_mappings(conn, &ids).await?,
+        None => type_mapping_query_many(conn, &ids).await?,
+    };
+
+    let mut models = Vec::new();
+    let mut errors = Vec::new();
+    for (id, name) in model_ids {
+        // only the fields selected in the query are fetched, unless the model has computed fields
+        // which may read any of them
+        let has_computed_fields = computed_fields
+            .is_some_and(|computed_fields| !computed_fields.fields(&name).is_empty());
+        let type_mapping = &match selection {
+            Some(selection) if !has_computed_fields => {
+                project_type_mapping(&type_mappings[&id], &[selection], field_casing)
+            }
+            _ => type_mappings[&id].clone(),
+        };
+
+        // but the table name for the model data is the unhashed model name
+        let data = model_data_batched_query(
+            conn,
+            vec![name.clone()],
+            &entity_id,
+            &[],
+            type_mapping,
+            list_batch_size.0,
+            MAX_NESTING_DEPTH,
+        )
+        .await
+        .and_then(|data| match data {
+            Value::Object(data) => Ok(Some(data)),
+            // model data has been deleted
+            Value::Null => Ok(None),
+            _ => Err(GraphqlObjectError::MalformedData(name.clone())),
+        });
+
+        match data {
+            Ok(Some(data)) => models.push((name, data)),
+            Ok(None) => continue,
+            Err(error) if partial => errors.push(error),
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok((models, errors))
+}
+
+// The data of the models of the entities last sent by a subscription, by entity id and model name
+type EntityModelsStates = Mutex<HashMap<String, HashMap<String, ValueMapping>>>;
+
+// The data of the models of an updated entity which changed since it was last sent, the models it
+// was not sent with yet being sent whole. The internal keys are always kept, and the nested values
+// are compared as a whole
+async fn entity_models_delta(
+    pool: &Pool<Sqlite>,
+    entity: &ValueMapping,
+    states: &EntityModelsStates,
+    list_batch_size: ListBatchSize,
+) -> Result<Value, GraphqlObjectError> {
+    let entity_id = extract::<String>(entity, "id")?;
+    let mut conn = pool.acquire().await?;
+    let (models, _) = entity_models(
+        &mut conn,
+        entity,
+        &ValueMapping::new(),
+        None,
+        FieldCasing::default(),
+        None,
+        None,
+        list_batch_size,
+    )
+    .await?;
+
+    let mut states = states.lock().unwrap();
+    let previous_models = states.entry(entity_id).or_default();
+
+    let mut delta = ValueMapping::new();
+    for (name, data) in models {
+        let changed_data: ValueMapping = match previous_models.get(&name) {
+            Some(previous_data) => data
+                .iter()
+                .filter(|(field, value)| {
+                    field.as_str().starts_with('$') || previous_data.get(*field) != Some(*value)
+                })
+                .map(|(field, value)| (field.clone(), value.clone()))
+                .collect(),
+            None => data.clone(),
+        };
+
+        if changed_data.keys().any(|field| !field.as_str().starts_with('$')) {
+            delta.insert(Name::new(&name), Value::Object(changed_data));
+        }
+        previous_models.insert(name, data);
+    }
+
+    Ok(Value::Object(delta))
+}
+
+// A missing model table is reported as such, the underlying sqlx error being logged rather than
+// exposing the database internals to the client
+fn model_data_error(model_name: &str, error: sqlx::Error) -> GraphqlObjectError {
+    match &error {
+        sqlx::Error::Database(db_error) if db_error.message().starts_with("no such table") => {
+            error!(target: LOG_TARGET, model = %model_name, %error, "Model table not found.");
+
+            GraphqlObjectError::TableNotFound(model_name.to_string())
+        }
+        _ => error.into(),
+    }
+}
+
+// Model data is fetched with one query per model table (the model and each of its nested structs
+// and lists), whatever the number of rows stored for the entity, and is then assembled in memory.
 pub async fn model_data_recursive_query(
     conn: &mut PoolConnection<Sqlite>,
     path_array: Vec<String>,
     entity_id: &str,
-    idx: Option<i64>,
+    indexes: &[i64],
     type_mapping: &TypeMapping,
-) -> sqlx::Result<Value> {
-    // For nested types, we need to remove prefix in path array
+) -> Result<Value, GraphqlObjectError> {
+    model_data_batched_query(
+        conn,
+        path_array,
+        entity_id,
+        indexes,
+        type_mapping,
+        ListBatchSize::default().0,
+        MAX_NESTING_DEPTH,
+    )
+    .await
+}
+
+// Same as `model_data_recursive_query`, the rows of the tables nested in arrays being fetched by
+// batches of `batch_size` rows and decoded batch by batch, so that only one batch of raw rows is
+// held in memory at a time whatever the length of the arrays. The types nested deeper than
+// `max_depth` structs, lists or options are rejected before their tables are queried.
+pub async fn model_data_batched_query(
+    conn: &mut PoolConnection<Sqlite>,
+    path_array: Vec<String>,
+    entity_id: &str,
+    indexes: &[i64],
+    type_mapping: &TypeMapping,
+    batch_size: u64,
+    max_depth: usize,
+) -> Result<Value, GraphqlObjectError> {
+    // the queries of the model tables and the decoding of their rows are grouped by model and
+    // entity, within the span of the request
+    let span = debug_span!(target: LOG_TARGET, "model_data", model = %path_array[0], entity_id);
+
+    let mut tables = HashMap::new();
+    fetch_model_tables(
+        conn,
+        path_array.clone(),
+        entity_id,
+        None,
+        type_mapping,
+        0,
+        0,
+        max_depth,
+        batch_size,
+        &mut tables,
+    )
+    .instrument(span.clone())
+    .await
+    .map_err(|error| match error {
+        GraphqlObjectError::Sql(error) => model_data_error(&path_array[0], error),
+        error => error,
+    })?;
+
+    span.in_scope(|| build_model_data(&mut tables, path_array, indexes, type_mapping, false))
+}
+
+// The element at `index` of the list at `path_array` (the path of its table), only the rows of that
+// element being fetched from the tables of the list. Null if the list has no such element
+pub async fn model_list_element_query(
+    conn: &mut PoolConnection<Sqlite>,
+    path_array: Vec<String>,
+    entity_id: &str,
+    index: i64,
+    inner: &TypeData,
+    batch_size: u64,
+) -> Result<Value, GraphqlObjectError> {
+    let span = debug_span!(target: LOG_TARGET, "model_data", model = %path_array[0], entity_id);
+
+    let list_mapping = IndexMap::from([(Name::new("data"), inner.clone())]);
+    let mut tables = HashMap::new();
+    fetch_model_tables(
+        conn,
+        path_array.clone(),
+        entity_id,
+        Some(index),
+        &list_mapping,
+        1,
+        path_array.len() - 1,
+        MAX_NESTING_DEPTH,
+        batch_size,
+        &mut tables,
+    )
+    .instrument(span.clone())
+    .await
+    .map_err(|error| match error {
+        GraphqlObjectError::Sql(error) => model_data_error(&path_array[0], error),
+        error => error,
+    })?;
+
+    let element = span
+        .in_scope(|| build_model_data(&mut tables, path_array, &[index], &list_mapping, false))?;
+    match element {
+        Value::Object(mut element) => {
+            Ok(element.shift_remove(&Name::new("data")).unwrap_or(Value::Null))
+        }
+        _ => Ok(Value::Null),
+    }
+}
+
+// For nested types, we need to remove prefix in path array
+fn model_table_name(path_array: &[String]) -> String {
     let namespace = format!("{}_", path_array[0]);
-    let table_name = &path_array.join("$").replace(&namespace, "");
-    let mut query = format!("SELECT * FROM {} WHERE entity_id = '{}' ", table_name, entity_id);
-    if let Some(idx) = idx {
-        query.push_str(&format!("AND idx = {}", idx));
+    path_array.join("$").replace(&namespace, "")
+}
+
+// The rows of a model table decoded along with their indexes in the arrays the table is nested in,
+// ordered by those indexes.
+type ModelTableRows = VecDeque<(Vec<i64>, ValueMapping)>;
+
+#[async_recursion]
+#[allow(clippy::too_many_arguments)]
+async fn fetch_model_tables(
+    conn: &mut PoolConnection<Sqlite>,
+    path_array: Vec<String>,
+    entity_id: &str,
+    list_index: Option<i64>,
+    type_mapping: &TypeMapping,
+    dimensions: usize,
+    depth: usize,
+    max_depth: usize,
+    batch_size: u64,
+    tables: &mut HashMap<String, ModelTableRows>,
+) -> Result<(), GraphqlObjectError> {
+    if depth > max_depth {
+        return Err(GraphqlObjectError::NestingTooDeep(path_array[0].clone(), max_depth));
     }
 
-    let rows = sqlx::query(&query).fetch_all(conn.as_mut()).await?;
-    if rows.is_empty() {
-        return Ok(Value::Null);
+    let table_name = model_table_name(&path_array);
+    // table names can't be bound, so make sure they can't break out of the query
+    validate_identifier(&table_name)?;
+
+    trace!(target: LOG_TARGET, entity_id, %table_name, "Fetching model table data.");
+
+    let columns = model_table_columns(type_mapping, dimensions)?;
+
+    let mut table_rows = VecDeque::new();
+    if dimensions == 0 {
+        let query = format!("SELECT {} FROM {} WHERE entity_id = ?", columns, table_name);
+        let row = sqlx::query(&query).bind(entity_id).fetch_optional(conn.as_mut()).await?;
+        if let Some(row) = row {
+            table_rows.push_back((vec![], value_mapping_from_row(&row, type_mapping, true)?));
+        }
+    } else {
+        let index_columns =
+            (0..dimensions).map(|idx| format!("idx_{}", idx)).collect::<Vec<_>>().join(", ");
+        // the rows of a single element of the outermost list are the ones sharing its index
+        let index_condition = if list_index.is_some() { " AND idx_0 = ?" } else { "" };
+        let query = format!(
+            "SELECT {} FROM {} WHERE entity_id = ?{} ORDER BY {} LIMIT ? OFFSET ?",
+            columns, table_name, index_condition, index_columns
+        );
+
+        let mut offset = 0;
+        loop {
+            trace!(target: LOG_TARGET, entity_id, %table_name, offset, "Fetching model list batch.");
+
+            let mut query = sqlx::query(&query).bind(entity_id);
+            if let Some(list_index) = list_index {
+                query = query.bind(list_index);
+            }
+            let rows =
+                query.bind(batch_size as i64).bind(offset as i64).fetch_all(conn.as_mut()).await?;
+
+            for row in &rows {
+                let row_indexes = (0..dimensions)
+                    .map(|idx| row.try_get::<i64, &str>(&format!("idx_{}", idx)))
+                    .collect::<sqlx::Result<Vec<_>>>()?;
+                table_rows
+                    .push_back((row_indexes, value_mapping_from_row(row, type_mapping, true)?));
+            }
+
+            if (rows.len() as u64) < batch_size {
+                break;
+            }
+            offset += batch_size;
+        }
     }
+    tables.insert(table_name, table_rows);
 
-    let value_mapping: Value;
-    let mut nested_value_mappings = Vec::new();
+    for (field_name, type_data) in type_mapping {
+        let mut nested_path = path_array.clone();
+        nested_path.push(field_name.to_string());
+
+        if let Some(nested_mapping) = type_data.type_mapping() {
+            trace!(target: LOG_TARGET, entity_id, %field_name, "Fetching nested model data.");
+
+            fetch_model_tables(
+                conn,
+                nested_path,
+                entity_id,
+                list_index,
+                nested_mapping,
+                dimensions,
+                depth + 1,
+                max_depth,
+                batch_size,
+                tables,
+            )
+            .await?;
+        } else if let TypeData::List(inner) = type_data {
+            trace!(target: LOG_TARGET, entity_id, %field_name, "Fetching model list data.");
+
+            let list_mapping = IndexMap::from([(Name::new("data"), *inner.clone())]);
+            fetch_model_tables(
+                conn,
+                nested_path,
+                entity_id,
+                list_index,
+                &list_mapping,
+                dimensions + 1,
+                depth + 1,
+                max_depth,
+                batch_size,
+                tables,
+            )
+            .await?;
+        } else if let TypeData::Option(inner) = type_data {
+            trace!(target: LOG_TARGET, entity_id, %field_name, "Fetching model option data.");
+
+            fetch_model_tables(
+                conn,
+                nested_path,
+                entity_id,
+                list_index,
+                &option_type_mapping(inner),
+                dimensions,
+                depth + 1,
+                max_depth,
+                batch_size,
+                tables,
+            )
+            .await?;
+        }
+    }
 
-    for (idx, row) in rows.iter().enumerate() {
-        let mut nested_value_mapping = value_mapping_from_row(row, type_mapping, true)?;
+    Ok(())
+}
+
+// Only the columns of the simple fields of a model table are fetched, along with the ones
+// identifying its rows, the other fields being stored in their own tables
+fn model_table_columns(type_mapping: &TypeMapping, dimensions: usize) -> sqlx::Result<String> {
+    let mut columns = vec![ENTITY_ID_COLUMN.to_string()];
+    columns.extend((0..dimensions).map(|idx| format!("idx_{}", idx)));
+
+    for (field_name, _) in type_mapping.iter().filter(|(_, type_data)| type_data.is_simple()) {
+        let column = format!("external_{}", field_name);
+        validate_identifier(&column)?;
+        columns.push(column);
+    }
+
+    Ok(columns.join(", "))
+}
+
+// Rows of tables nested in arrays are identified by their position in each of those arrays
+// (`idx_0` for the outermost one), the data of a row being stored in tables nested in the same
+// arrays, or in one more array for lists, and sharing its indexes.
+//
+// As the rows of each table and their parents are ordered by their indexes, the rows of a parent
+// are the first ones left in its tables, so the rows are moved out of the tables as they are built.
+fn build_model_data(
+    tables: &mut HashMap<String, ModelTableRows>,
+    path_array: Vec<String>,
+    indexes: &[i64],
+    type_mapping: &TypeMapping,
+    is_list: bool,
+) -> Result<Value, GraphqlObjectError> {
+    let table_name = model_table_name(&path_array);
+
+    let mut rows = Vec::new();
+    if let Some(table_rows) = tables.get_mut(&table_name) {
+        while let Some((row_indexes, _)) = table_rows.front() {
+            if row_indexes.starts_with(indexes) {
+                rows.extend(table_rows.pop_front());
+            } else if row_indexes.as_slice() < indexes {
+                // orphan rows, whose parent doesn't exist anymore
+                table_rows.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
 
+    let mut nested_value_mappings = Vec::new();
+
+    for (row_indexes, mut nested_value_mapping) in rows {
         for (field_name, type_data) in type_mapping {
-            if let TypeData::Nested((_, nested_mapping)) = type_data {
+            if let Some(nested_mapping) = type_data.type_mapping() {
                 let mut nested_path = path_array.clone();
                 nested_path.push(field_name.to_string());
 
-                let nested_values = model_data_recursive_query(
-                    conn,
-                    nested_path,
-                    entity_id,
-                    if rows.len() > 1 { Some(idx as i64) } else { None },
-                    nested_mapping,
-                )
-                .await?;
+                let nested_values =
+                    build_model_data(tables, nested_path, &row_indexes, nested_mapping, false)?;
 
                 nested_value_mapping.insert(Name::new(field_name), nested_values);
             } else if let TypeData::List(inner) = type_data {
                 let mut nested_path = path_array.clone();
                 nested_path.push(field_name.to_string());
 
-                let data = match model_data_recursive_query(
-                    conn,
+                let Value::List(elements) = build_model_data(
+                    tables,
                     nested_path,
-                    entity_id,
-                    // this might need to be changed to support 2d+ arrays
-                    None,
+                    &row_indexes,
                     &IndexMap::from([(Name::new("data"), *inner.clone())]),
-                )
-                .await?
-                {
-                    // map our list which uses a data field as a place holder
-                    // for all elements to get the elemnt directly
-                    Value::List(data) => data
-                        .iter()
-                        .map(|v| match v {
-                            Value::Object(map) => map.get(&Name::new("data")).unwrap().clone(),
-                            _ => unreachable!(),
-                        })
-                        .collect(),
-                    _ => unreachable!(),
+                    true,
+                )?
+                else {
+                    return Err(GraphqlObjectError::MalformedData(table_name));
                 };
 
-                nested_value_mapping.insert(Name::new(field_name), data);
+                // map our list which uses a data field as a place holder
+                // for all elements to get the elemnt directly
+                let data = elements
+                    .into_iter()
+                    .map(|element| match element {
+                        Value::Object(map) => map.get(&Name::new("data")).cloned(),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| GraphqlObjectError::MalformedData(table_name.clone()))?;
+
+                nested_value_mapping.insert(Name::new(field_name), Value::List(data));
+            } else if let TypeData::Option(inner) = type_data {
+                let mut nested_path = path_array.clone();
+                nested_path.push(field_name.to_string());
+
+                let option = build_model_data(
+                    tables,
+                    nested_path,
+                    &row_indexes,
+                    &option_type_mapping(inner),
+                    false,
+                )?;
+
+                nested_value_mapping.insert(Name::new(field_name), option_value(option));
             }
         }
 
         nested_value_mappings.push(Value::Object(nested_value_mapping));
     }
 
-    if nested_value_mappings.len() > 1 {
-        value_mapping = Value::List(nested_value_mappings);
-    } else {
-        value_mapping = nested_value_mappings.pop().unwrap();
+    if is_list {
+        return Ok(Value::List(nested_value_mappings));
     }
 
-    Ok(value_mapping)
+    Ok(nested_value_mappings.pop().unwrap_or(Value::Null))
 }
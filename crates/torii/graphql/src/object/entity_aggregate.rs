@@ -0,0 +1,96 @@
+use std::str::FromStr;
+
+use async_graphql::dynamic::indexmap::IndexMap;
+use async_graphql::dynamic::{Field, FieldFuture, InputValue, TypeRef};
+use async_graphql::{Name, Value};
+use dojo_types::primitive::{Primitive, SqlType};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Pool, Row, Sqlite};
+
+use super::{BasicObject, TypeMapping, ValueMapping};
+use crate::constants::{ENTITY_AGGREGATE_NAMES, ENTITY_AGGREGATE_TYPE_NAME};
+use crate::mapping::ENTITY_AGGREGATE_TYPE_MAPPING;
+use crate::query::data::{fetch_aggregates, fetch_member_type};
+
+pub struct EntityAggregateObject;
+
+impl BasicObject for EntityAggregateObject {
+    fn name(&self) -> (&str, &str) {
+        ENTITY_AGGREGATE_NAMES
+    }
+
+    fn type_name(&self) -> &str {
+        ENTITY_AGGREGATE_TYPE_NAME
+    }
+
+    fn type_mapping(&self) -> &TypeMapping {
+        &ENTITY_AGGREGATE_TYPE_MAPPING
+    }
+}
+
+impl EntityAggregateObject {
+    pub fn value_mapping(row: &SqliteRow) -> sqlx::Result<ValueMapping> {
+        Ok(IndexMap::from([
+            (Name::new("count"), Value::from(row.try_get::<i64, _>("count")?)),
+            (
+                Name::new("min"),
+                row.try_get::<Option<i64>, _>("min")?.map_or(Value::Null, Value::from),
+            ),
+            (
+                Name::new("max"),
+                row.try_get::<Option<i64>, _>("max")?.map_or(Value::Null, Value::from),
+            ),
+            (
+                Name::new("sum"),
+                row.try_get::<Option<i64>, _>("sum")?.map_or(Value::Null, Value::from),
+            ),
+            (
+                Name::new("avg"),
+                row.try_get::<Option<f64>, _>("avg")?.map_or(Value::Null, Value::from),
+            ),
+        ]))
+    }
+}
+
+// Aggregates the entities, and a member of their model if given. Only the members stored as
+// integers can be aggregated, the bigger numbers being stored as hexadecimal strings
+pub fn entity_aggregate_field() -> Field {
+    Field::new(ENTITY_AGGREGATE_NAMES.0, TypeRef::named_nn(ENTITY_AGGREGATE_TYPE_NAME), |ctx| {
+        FieldFuture::new(async move {
+            let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+            let model = ctx.args.get("model").map(|model| model.string()).transpose()?;
+            let member = ctx.args.get("member").map(|member| member.string()).transpose()?;
+
+            if let Some(member) = member {
+                let Some(model) = model else {
+                    return Err("A member is aggregated along with its model.".into());
+                };
+
+                let Some(ty) = fetch_member_type(&mut conn, model, member).await? else {
+                    return Err(format!("The model '{}' has no member '{}'.", model, member).into());
+                };
+
+                if !is_numeric(&ty) {
+                    return Err(format!(
+                        "The member '{}' of the model '{}' is a {}, only the u8, u16, u32 and \
+                         usize members can be aggregated.",
+                        member, model, ty
+                    )
+                    .into());
+                }
+            }
+
+            let row = fetch_aggregates(&mut conn, model, member).await?;
+            Ok(Some(Value::Object(EntityAggregateObject::value_mapping(&row)?)))
+        })
+    })
+    .argument(InputValue::new("model", TypeRef::named(TypeRef::STRING)))
+    .argument(InputValue::new("member", TypeRef::named(TypeRef::STRING)))
+}
+
+fn is_numeric(ty: &str) -> bool {
+    match Primitive::from_str(ty) {
+        Ok(Primitive::Bool(_)) | Err(_) => false,
+        Ok(primitive) => primitive.to_sql_type() == SqlType::Integer,
+    }
+}
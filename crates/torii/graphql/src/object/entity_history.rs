@@ -0,0 +1,72 @@
+use async_graphql::dynamic::{Field, FieldFuture, TypeRef};
+use async_graphql::{ErrorExtensions, Value};
+use sqlx::{Pool, Sqlite};
+
+use super::{BasicObject, TypeMapping};
+use crate::constants::{
+    ENTITY_HISTORY_NAMES, ENTITY_HISTORY_TABLE, ENTITY_HISTORY_TYPE_NAME, ENTITY_ID_COLUMN,
+};
+use crate::error::GraphqlObjectError;
+use crate::mapping::ENTITY_HISTORY_TYPE_MAPPING;
+use crate::query::value_mapping_from_row;
+use crate::utils::extract;
+
+pub struct EntityHistoryObject;
+
+impl BasicObject for EntityHistoryObject {
+    fn name(&self) -> (&str, &str) {
+        ENTITY_HISTORY_NAMES
+    }
+
+    fn type_name(&self) -> &str {
+        ENTITY_HISTORY_TYPE_NAME
+    }
+
+    fn type_mapping(&self) -> &TypeMapping {
+        &ENTITY_HISTORY_TYPE_MAPPING
+    }
+}
+
+// The events which set an entity, from the first to the last one. The entities table only keeps
+// the last event, the previous ones being logged in a table which the databases indexed before it
+// was added don't have
+pub fn entity_history_field() -> Field {
+    Field::new(ENTITY_HISTORY_NAMES.0, TypeRef::named_nn_list_nn(ENTITY_HISTORY_TYPE_NAME), |ctx| {
+        FieldFuture::new(async move {
+            let Value::Object(indexmap) = ctx.parent_value.try_to_value()? else {
+                return Err(GraphqlObjectError::UnexpectedValue("Object").extend());
+            };
+            let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+
+            let entity_id = extract::<String>(indexmap, "id")?;
+            let query = format!(
+                "SELECT event_id, executed_at FROM {ENTITY_HISTORY_TABLE} WHERE \
+                 {ENTITY_ID_COLUMN} = ? ORDER BY executed_at ASC, rowid ASC"
+            );
+            let rows = sqlx::query(&query)
+                .bind(&entity_id)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|error| history_error(error).extend())?;
+
+            let history = rows
+                .iter()
+                .map(|row| {
+                    value_mapping_from_row(row, &ENTITY_HISTORY_TYPE_MAPPING, false)
+                        .map(Value::Object)
+                })
+                .collect::<sqlx::Result<Vec<_>>>()?;
+
+            Ok(Some(Value::List(history)))
+        })
+    })
+}
+
+fn history_error(error: sqlx::Error) -> GraphqlObjectError {
+    match &error {
+        sqlx::Error::Database(db_error) if db_error.message().starts_with("no such table") => {
+            GraphqlObjectError::HistoryNotFound
+        }
+        _ => error.into(),
+    }
+}
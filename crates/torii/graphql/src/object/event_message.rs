@@ -2,22 +2,25 @@ use async_graphql::dynamic::indexmap::IndexMap;
 use async_graphql::dynamic::{
     Field, FieldFuture, FieldValue, InputValue, SubscriptionField, SubscriptionFieldFuture, TypeRef,
 };
-use async_graphql::{Name, Value};
+use async_graphql::{ErrorExtensions, Name, Value};
 use async_recursion::async_recursion;
 use sqlx::pool::PoolConnection;
 use sqlx::{Pool, Sqlite};
 use tokio_stream::StreamExt;
 use torii_core::simple_broker::SimpleBroker;
 use torii_core::types::EventMessage;
+use tracing::{debug_span, Instrument};
 
 use super::inputs::keys_input::keys_argument;
 use super::{BasicObject, ResolvableObject, TypeMapping, ValueMapping};
 use crate::constants::{
     EVENT_ID_COLUMN, EVENT_MESSAGE_NAMES, EVENT_MESSAGE_TABLE, EVENT_MESSAGE_TYPE_NAME, ID_COLUMN,
+    MAX_NESTING_DEPTH,
 };
+use crate::error::GraphqlObjectError;
 use crate::mapping::ENTITY_TYPE_MAPPING;
 use crate::object::{resolve_many, resolve_one};
-use crate::query::{type_mapping_query, value_mapping_from_row};
+use crate::query::{option_type_mapping, option_value, type_mapping_query, value_mapping_from_row};
 use crate::types::TypeData;
 use crate::utils::extract;
 pub struct EventMessageObject;
@@ -63,35 +66,31 @@ impl ResolvableObject for EventMessageObject {
     }
 
     fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
-        Some(vec![
-            SubscriptionField::new(
-                "eventMessageUpdated",
-                TypeRef::named_nn(self.type_name()),
-                |ctx| {
-                    SubscriptionFieldFuture::new(async move {
-                        let id = match ctx.args.get("id") {
-                            Some(id) => Some(id.string()?.to_string()),
-                            None => None,
-                        };
-                        // if id is None, then subscribe to all entities
-                        // if id is Some, then subscribe to only the entity with that id
-                        Ok(SimpleBroker::<EventMessage>::subscribe().filter_map(
-                            move |entity: EventMessage| {
-                                if id.is_none() || id == Some(entity.id.clone()) {
-                                    Some(Ok(Value::Object(EventMessageObject::value_mapping(
-                                        entity,
-                                    ))))
-                                } else {
-                                    // id != entity.id , then don't send anything, still listening
-                                    None
-                                }
-                            },
-                        ))
-                    })
-                },
-            )
-            .argument(InputValue::new("id", TypeRef::named(TypeRef::ID))),
-        ])
+        Some(vec![SubscriptionField::new(
+            "eventMessageUpdated",
+            TypeRef::named_nn(self.type_name()),
+            |ctx| {
+                SubscriptionFieldFuture::new(async move {
+                    let id = match ctx.args.get("id") {
+                        Some(id) => Some(id.string()?.to_string()),
+                        None => None,
+                    };
+                    // if id is None, then subscribe to all entities
+                    // if id is Some, then subscribe to only the entity with that id
+                    Ok(SimpleBroker::<EventMessage>::subscribe().filter_map(
+                        move |entity: EventMessage| {
+                            if id.is_none() || id == Some(entity.id.clone()) {
+                                Some(Ok(Value::Object(EventMessageObject::value_mapping(entity))))
+                            } else {
+                                // id != entity.id , then don't send anything, still listening
+                                None
+                            }
+                        },
+                    ))
+                })
+            },
+        )
+        .argument(InputValue::new("id", TypeRef::named(TypeRef::ID)))])
     }
 }
 
@@ -148,8 +147,11 @@ fn model_union_field() -> Field {
                             vec![name.clone()],
                             &entity_id,
                             &type_mapping,
+                            0,
                         )
-                        .await?;
+                        .instrument(debug_span!("model_data", model = %name, %entity_id))
+                        .await
+                        .map_err(|error| error.extend())?;
 
                         results.push(FieldValue::with_type(FieldValue::owned_any(data), name));
                     }
@@ -163,13 +165,19 @@ fn model_union_field() -> Field {
 }
 
 // TODO: flatten query
+// The types nested deeper than `MAX_NESTING_DEPTH` are rejected before their tables are queried
 #[async_recursion]
 pub async fn model_data_recursive_query(
     conn: &mut PoolConnection<Sqlite>,
     path_array: Vec<String>,
     entity_id: &str,
     type_mapping: &TypeMapping,
-) -> sqlx::Result<ValueMapping> {
+    depth: usize,
+) -> Result<ValueMapping, GraphqlObjectError> {
+    if depth > MAX_NESTING_DEPTH {
+        return Err(GraphqlObjectError::NestingTooDeep(path_array[0].clone(), MAX_NESTING_DEPTH));
+    }
+
     // For nested types, we need to remove prefix in path array
     let namespace = format!("{}_", path_array[0]);
     let table_name = &path_array.join("$").replace(&namespace, "");
@@ -178,14 +186,36 @@ pub async fn model_data_recursive_query(
     let mut value_mapping = value_mapping_from_row(&row, type_mapping, true)?;
 
     for (field_name, type_data) in type_mapping {
-        if let TypeData::Nested((_, nested_mapping)) = type_data {
+        // the tables of the enum variants which are not set may have no row
+        if let Some(option) = value_mapping.get(&Name::new("option")) {
+            if option != &Value::from(field_name.as_str()) {
+                continue;
+            }
+        }
+
+        if let Some(nested_mapping) = type_data.type_mapping() {
             let mut nested_path = path_array.clone();
             nested_path.push(field_name.to_string());
 
             let nested_values =
-                model_data_recursive_query(conn, nested_path, entity_id, nested_mapping).await?;
+                model_data_recursive_query(conn, nested_path, entity_id, nested_mapping, depth + 1)
+                    .await?;
 
             value_mapping.insert(Name::new(field_name), Value::Object(nested_values));
+        } else if let TypeData::Option(inner) = type_data {
+            let mut nested_path = path_array.clone();
+            nested_path.push(field_name.to_string());
+
+            let option = model_data_recursive_query(
+                conn,
+                nested_path,
+                entity_id,
+                &option_type_mapping(inner),
+                depth + 1,
+            )
+            .await?;
+
+            value_mapping.insert(Name::new(field_name), option_value(Value::Object(option)));
         }
     }
 
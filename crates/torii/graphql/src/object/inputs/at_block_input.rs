@@ -0,0 +1,18 @@
+use async_graphql::dynamic::{Field, InputValue, ResolverContext, TypeRef};
+use async_graphql::Result;
+
+pub fn at_block_argument(field: Field) -> Field {
+    field.argument(InputValue::new("at_block", TypeRef::named(TypeRef::INT)))
+}
+
+// Torii only stores the latest state of the entities, without the block height of their updates,
+// so their state at a past block can't be resolved. The argument is rejected rather than ignored.
+pub fn parse_at_block_argument(ctx: &ResolverContext<'_>) -> Result<()> {
+    if ctx.args.get("at_block").is_some() {
+        return Err("The at_block argument is not supported, torii does not store the history of \
+                    the entities."
+            .into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use async_graphql::dynamic::{Field, InputObject, InputValue, ResolverContext, TypeRef};
+use async_graphql::Result;
+
+use crate::constants::{ENTITY_ORDER_INPUT_TYPE_NAME, ORDER_DIR_TYPE_NAME};
+use crate::query::order::{Direction, Order};
+
+// Timestamps of the entities they can also be ordered by, along with their columns. The raw values
+// of the columns are compared, rather than the formatted datetimes
+const TIMESTAMP_FIELDS: [(&str, &str); 3] =
+    [("createdAt", "created_at"), ("updatedAt", "updated_at"), ("executedAt", "executed_at")];
+
+pub fn entity_order_input_object() -> InputObject {
+    InputObject::new(ENTITY_ORDER_INPUT_TYPE_NAME)
+        .field(InputValue::new("field", TypeRef::named_nn(TypeRef::STRING)))
+        .field(InputValue::new("direction", TypeRef::named_nn(ORDER_DIR_TYPE_NAME)))
+}
+
+pub fn entity_order_argument(field: Field) -> Field {
+    field.argument(InputValue::new("orderBy", TypeRef::named(ENTITY_ORDER_INPUT_TYPE_NAME)))
+}
+
+// The field is given as "Model.member", the entities being ordered by the member of their model, or
+// as one of the timestamps of the entities. Returns the model, if any, along with the order on its
+// member or on the timestamp column
+pub fn parse_entity_order_argument(
+    ctx: &ResolverContext<'_>,
+) -> Result<Option<(Option<String>, Order)>> {
+    let Some(order_input) = ctx.args.get("orderBy") else {
+        return Ok(None);
+    };
+
+    let order_input = order_input.object()?;
+    let field = order_input.try_get("field")?.string()?;
+    let direction = Direction::try_from(order_input.try_get("direction")?.enum_name()?)?;
+
+    if let Some((_, column)) = TIMESTAMP_FIELDS.iter().find(|(name, _)| *name == field) {
+        return Ok(Some((None, Order { field: column.to_string(), direction })));
+    }
+
+    let Some((model, member)) = field.split_once('.') else {
+        return Err(format!(
+            "Entities are ordered by a model member given as `Model.member`, or by one of their \
+             timestamps, got `{}`.",
+            field
+        )
+        .into());
+    };
+
+    Ok(Some((Some(model.to_string()), Order { field: member.to_string(), direction })))
+}
@@ -0,0 +1,67 @@
+use async_graphql::dynamic::{Field, InputObject, InputValue, ResolverContext, TypeRef};
+use async_graphql::Result;
+
+use crate::constants::ENTITY_WHERE_INPUT_TYPE_NAME;
+use crate::query::filter::{Comparator, MemberFilter};
+
+// Comparators available to filter entities on the members of their models, each of them is
+// exposed as a field of the input object (eg "gt", "lte")
+const MEMBER_COMPARATORS: [Comparator; 6] = [
+    Comparator::Eq,
+    Comparator::Neq,
+    Comparator::Gt,
+    Comparator::Gte,
+    Comparator::Lt,
+    Comparator::Lte,
+];
+
+pub fn entity_where_input_object() -> InputObject {
+    let input_object = InputObject::new(ENTITY_WHERE_INPUT_TYPE_NAME)
+        .field(InputValue::new("model", TypeRef::named_nn(TypeRef::STRING)))
+        .field(InputValue::new("member", TypeRef::named_nn(TypeRef::STRING)));
+
+    MEMBER_COMPARATORS.iter().fold(input_object, |acc, comparator| {
+        acc.field(InputValue::new(
+            comparator.as_ref().to_lowercase(),
+            TypeRef::named(TypeRef::STRING),
+        ))
+    })
+}
+
+pub fn entity_where_argument(field: Field) -> Field {
+    field.argument(InputValue::new("where", TypeRef::named_nn_list(ENTITY_WHERE_INPUT_TYPE_NAME)))
+}
+
+// Every condition of the list, and every comparator within a condition, are combined with AND
+pub fn parse_entity_where_argument(ctx: &ResolverContext<'_>) -> Result<Option<Vec<MemberFilter>>> {
+    let Some(where_input) = ctx.args.get("where") else {
+        return Ok(None);
+    };
+
+    let mut filters = Vec::new();
+    for condition in where_input.list()?.iter() {
+        let condition = condition.object()?;
+        let model = condition.try_get("model")?.string()?;
+        let member = condition.try_get("member")?.string()?;
+
+        for comparator in MEMBER_COMPARATORS {
+            if let Some(value) = condition.get(&comparator.as_ref().to_lowercase()) {
+                let value = value.string()?;
+                // felts, addresses and large integers are stored as padded lowercase hex strings
+                let value = match value.strip_prefix("0x") {
+                    Some(hex) => format!("0x{:0>64}", hex.to_lowercase()),
+                    None => value.to_string(),
+                };
+
+                filters.push(MemberFilter {
+                    model: model.to_string(),
+                    member: member.to_string(),
+                    comparator,
+                    value,
+                });
+            }
+        }
+    }
+
+    Ok(Some(filters))
+}
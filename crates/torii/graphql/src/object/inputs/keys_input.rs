@@ -1,6 +1,8 @@
-use async_graphql::dynamic::{Field, InputValue, ResolverContext, TypeRef};
+use async_graphql::dynamic::{Field, InputObject, InputValue, ResolverContext, TypeRef};
 use async_graphql::Error;
 
+use crate::constants::KEYS_RANGE_INPUT_TYPE_NAME;
+use crate::query::filter::KeyRange;
 use crate::utils::extract;
 
 pub fn keys_argument(field: Field) -> Field {
@@ -21,6 +23,64 @@ pub fn parse_keys_argument(ctx: &ResolverContext<'_>) -> Result<Option<Vec<Strin
     Ok(None)
 }
 
+pub fn keys_range_input_object() -> InputObject {
+    InputObject::new(KEYS_RANGE_INPUT_TYPE_NAME)
+        .field(InputValue::new("position", TypeRef::named_nn(TypeRef::INT)))
+        .field(InputValue::new("min", TypeRef::named(TypeRef::STRING)))
+        .field(InputValue::new("max", TypeRef::named(TypeRef::STRING)))
+}
+
+pub fn keys_range_argument(field: Field) -> Field {
+    field.argument(InputValue::new("keysRange", TypeRef::named_nn_list(KEYS_RANGE_INPUT_TYPE_NAME)))
+}
+
+// Every range of the list is combined with AND, the bounds being normalized to hex strings without
+// leading zeros, as the keys are stored
+pub fn parse_keys_range_argument(
+    ctx: &ResolverContext<'_>,
+) -> Result<Option<Vec<KeyRange>>, Error> {
+    let Some(keys_range) = ctx.args.get("keysRange") else {
+        return Ok(None);
+    };
+
+    let mut key_ranges = Vec::new();
+    for range in keys_range.list()?.iter() {
+        let range = range.object()?;
+        let position = range.try_get("position")?.i64()?;
+        let position = usize::try_from(position)
+            .map_err(|_| format!("The key position must be non-negative, got {}.", position))?;
+
+        let bound = |name: &str| -> Result<Option<String>, Error> {
+            match range.get(name) {
+                Some(bound) => normalize_key_bound(bound.string()?).map(Some),
+                None => Ok(None),
+            }
+        };
+
+        key_ranges.push(KeyRange { position, min: bound("min")?, max: bound("max")? });
+    }
+
+    Ok(Some(key_ranges))
+}
+
+fn normalize_key_bound(bound: &str) -> Result<String, Error> {
+    let hex = match bound.strip_prefix("0x") {
+        Some(hex) if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+            hex.trim_start_matches('0').to_lowercase()
+        }
+        _ => return Err(format!("Key bounds can only be hex strings, got `{}`.", bound).into()),
+    };
+
+    if hex.len() > 64 {
+        return Err(format!("Key bounds must fit in a felt, got `{}`.", bound).into());
+    }
+
+    match hex.is_empty() {
+        true => Ok("0x0".to_string()),
+        false => Ok(format!("0x{}", hex)),
+    }
+}
+
 fn is_hex_or_star(s: &str) -> bool {
     if s == "*" {
         return true;
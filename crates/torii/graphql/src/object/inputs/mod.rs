@@ -2,6 +2,9 @@ use async_graphql::dynamic::{Enum, InputObject};
 
 use super::TypeMapping;
 
+pub mod at_block_input;
+pub mod entity_order_input;
+pub mod entity_where_input;
 pub mod keys_input;
 pub mod order_input;
 pub mod where_input;
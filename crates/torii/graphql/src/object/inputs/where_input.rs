@@ -10,21 +10,24 @@ use strum::IntoEnumIterator;
 use super::InputObjectTrait;
 use crate::object::TypeMapping;
 use crate::query::filter::{parse_filter, Comparator, Filter, FilterValue};
-use crate::types::TypeData;
+use crate::types::{FieldCasing, TypeData};
 
 pub struct WhereInputObject {
     pub type_name: String,
     pub type_mapping: TypeMapping,
+    pub field_casing: FieldCasing,
 }
 
 impl WhereInputObject {
     // Iterate through an object's type mapping and create a new mapping for whereInput. For each of
     // the object type (model member), we add 6 additional types for comparators (great than,
     // not equal, etc)
-    pub fn new(type_name: &str, object_types: &TypeMapping) -> Self {
+    pub fn new(type_name: &str, object_types: &TypeMapping, field_casing: FieldCasing) -> Self {
         let where_mapping = object_types
             .iter()
-            .filter(|(_, type_data)| !type_data.is_nested() && !type_data.is_list())
+            .filter(|(_, type_data)| {
+                !type_data.is_nested() && !type_data.is_list() && !type_data.is_option()
+            })
             .flat_map(|(type_name, type_data)| {
                 // TODO: filter on nested and enum objects
                 if type_data.type_ref() == TypeRef::named("Enum")
@@ -54,7 +57,11 @@ impl WhereInputObject {
             })
             .collect();
 
-        Self { type_name: format!("{}WhereInput", type_name), type_mapping: where_mapping }
+        Self {
+            type_name: format!("{}WhereInput", type_name),
+            type_mapping: where_mapping,
+            field_casing,
+        }
     }
 }
 
@@ -69,11 +76,22 @@ impl InputObjectTrait for WhereInputObject {
 
     fn input_object(&self) -> InputObject {
         self.type_mapping.iter().fold(InputObject::new(self.type_name()), |acc, (ty_name, ty)| {
-            acc.field(InputValue::new(ty_name.to_string(), ty.type_ref()))
+            acc.field(InputValue::new(input_field_name(ty_name, self.field_casing), ty.type_ref()))
         })
     }
 }
 
+// The input fields are named after the model fields, with the casing of the schema, and followed
+// by their comparator (eg `playerScoreGT` for `player_scoreGT`)
+fn input_field_name(name: &str, field_casing: FieldCasing) -> String {
+    Comparator::iter()
+        .find_map(|comparator| {
+            name.strip_suffix(comparator.as_ref())
+                .map(|field| format!("{}{}", field_casing.apply(field), comparator.as_ref()))
+        })
+        .unwrap_or_else(|| field_casing.apply(name))
+}
+
 pub fn where_argument(field: Field, type_name: &str) -> Field {
     field.argument(InputValue::new("where", TypeRef::named(format!("{}WhereInput", type_name))))
 }
@@ -81,13 +99,15 @@ pub fn where_argument(field: Field, type_name: &str) -> Field {
 pub fn parse_where_argument(
     ctx: &ResolverContext<'_>,
     where_mapping: &TypeMapping,
+    field_casing: FieldCasing,
 ) -> Result<Option<Vec<Filter>>> {
     ctx.args.get("where").map_or(Ok(None), |where_input| {
         let input_object = where_input.object()?;
         where_mapping
             .iter()
             .filter_map(|(type_name, type_data)| {
-                input_object.get(type_name).map(|input| match type_data {
+                let input_name = input_field_name(type_name, field_casing);
+                input_object.get(&input_name).map(|input| match type_data {
                     TypeData::Simple(_) => {
                         if type_data.type_ref() == TypeRef::named("Enum") {
                             let value = input.string().unwrap();
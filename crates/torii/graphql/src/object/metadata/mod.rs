@@ -56,7 +56,8 @@ impl ResolvableObject for MetadataObject {
                 FieldFuture::new(async move {
                     let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
                     let connection = parse_connection_arguments(&ctx)?;
-                    let total_count = count_rows(&mut conn, METADATA_TABLE, &None, &None).await?;
+                    let total_count =
+                        count_rows(&mut conn, METADATA_TABLE, &None, &None, &None, &None).await?;
                     let (data, page_info) = fetch_multiple_rows(
                         &mut conn,
                         METADATA_TABLE,
@@ -64,6 +65,8 @@ impl ResolvableObject for MetadataObject {
                         &None,
                         &None,
                         &None,
+                        &None,
+                        &None,
                         &connection,
                         total_count,
                     )
@@ -1,5 +1,7 @@
 pub mod connection;
 pub mod entity;
+pub mod entity_aggregate;
+pub mod entity_history;
 pub mod event;
 pub mod event_message;
 pub mod inputs;
@@ -8,22 +10,36 @@ pub mod model;
 pub mod model_data;
 pub mod transaction;
 
+use std::collections::HashMap;
+
 use async_graphql::dynamic::{
-    Enum, Field, FieldFuture, InputObject, InputValue, Object, SubscriptionField, TypeRef,
+    Enum, Field, FieldFuture, InputObject, InputValue, Object, ResolverContext, SubscriptionField,
+    TypeRef,
 };
 use async_graphql::Value;
+use chrono::DateTime;
 use convert_case::{Case, Casing};
-use sqlx::{Pool, Sqlite};
+use sqlx::{Pool, Row, Sqlite};
+use starknet::core::types::FieldElement;
 
 use self::connection::edge::EdgeObject;
 use self::connection::{
     connection_arguments, connection_output, parse_connection_arguments, ConnectionObject,
 };
-use self::inputs::keys_input::parse_keys_argument;
+use self::inputs::at_block_input::parse_at_block_argument;
+use self::inputs::entity_order_input::parse_entity_order_argument;
+use self::inputs::entity_where_input::parse_entity_where_argument;
+use self::inputs::keys_input::{parse_keys_argument, parse_keys_range_argument};
 use self::inputs::order_input::parse_order_argument;
-use crate::query::data::{count_rows, fetch_multiple_rows, fetch_single_row};
+use crate::constants::{
+    KEYS_COLUMN, KEYS_FORMAT_DECIMAL, KEYS_FORMAT_HEX, KEYS_FORMAT_STRING, KEYS_FORMAT_TYPE_NAME,
+};
+use crate::query::data::{
+    count_rows, entity_order_table, fetch_max_key_count, fetch_multiple_rows, fetch_rows_by_ids,
+    fetch_single_row,
+};
 use crate::query::value_mapping_from_row;
-use crate::types::{TypeMapping, ValueMapping};
+use crate::types::{GraphqlType, TypeMapping, ValueMapping};
 use crate::utils::extract;
 
 pub enum ObjectVariant {
@@ -51,19 +67,36 @@ pub trait BasicObject: Send + Sync {
         let mut object = Object::new(self.type_name());
 
         for (field_name, type_data) in self.type_mapping().clone() {
-            let field = Field::new(field_name.to_string(), type_data.type_ref(), move |ctx| {
+            let is_datetime = type_data.type_ref().to_string() == GraphqlType::DateTime.to_string();
+            let is_keys = field_name.as_str() == KEYS_COLUMN;
+
+            let mut field = Field::new(field_name.to_string(), type_data.type_ref(), move |ctx| {
                 let field_name = field_name.clone();
 
                 FieldFuture::new(async move {
                     match ctx.parent_value.try_to_value()? {
                         Value::Object(values) => {
-                            Ok(Some(values.get(&field_name).unwrap().clone())) // safe unwrap
+                            let value = values.get(&field_name).unwrap().clone(); // safe unwrap
+                            match (is_datetime, is_keys) {
+                                (true, _) => Ok(Some(datetime_value(&ctx, value)?)),
+                                (_, true) => Ok(Some(keys_value(&ctx, value)?)),
+                                _ => Ok(Some(value)),
+                            }
                         }
                         _ => Err("incorrect value, requires Value::Object".into()),
                     }
                 })
             });
 
+            if is_datetime {
+                field = field.argument(InputValue::new("epoch", TypeRef::named(TypeRef::BOOLEAN)));
+            }
+
+            if is_keys {
+                field =
+                    field.argument(InputValue::new("as", TypeRef::named(KEYS_FORMAT_TYPE_NAME)));
+            }
+
             object = object.field(field);
         }
 
@@ -111,6 +144,47 @@ pub trait ResolvableObject: BasicObject {
     }
 }
 
+// Datetimes are resolved as formatted, or as Unix timestamps in seconds with the `epoch` argument
+fn datetime_value(ctx: &ResolverContext<'_>, value: Value) -> async_graphql::Result<Value> {
+    let epoch = match ctx.args.get("epoch") {
+        Some(epoch) => epoch.boolean()?,
+        None => false,
+    };
+
+    match value {
+        Value::String(datetime) if epoch => {
+            Ok(Value::from(DateTime::parse_from_rfc3339(&datetime)?.timestamp()))
+        }
+        value => Ok(value),
+    }
+}
+
+// Keys are resolved as stored (`STRING`, the default), or with the `as` argument as hexadecimals
+// padded to the size of a felt (`HEX`) or as decimals (`DECIMAL`), which may not fit in an Int
+fn keys_value(ctx: &ResolverContext<'_>, value: Value) -> async_graphql::Result<Value> {
+    let format = match ctx.args.get("as") {
+        Some(format) => format.enum_name()?,
+        None => KEYS_FORMAT_STRING,
+    };
+
+    match value {
+        Value::List(keys) if format != KEYS_FORMAT_STRING => keys
+            .into_iter()
+            .map(|key| match key {
+                Value::String(key) if format == KEYS_FORMAT_HEX => {
+                    Ok(Value::from(format!("0x{:0>64}", key.trim_start_matches("0x"))))
+                }
+                Value::String(key) if format == KEYS_FORMAT_DECIMAL => {
+                    Ok(Value::from(FieldElement::from_hex_be(&key)?.to_string()))
+                }
+                key => Ok(key),
+            })
+            .collect::<async_graphql::Result<Vec<_>>>()
+            .map(Value::List),
+        value => Ok(value),
+    }
+}
+
 // Resolves single object queries, returns current object of type type_name (eg "Player")
 pub fn resolve_one(
     table_name: &str,
@@ -130,6 +204,7 @@ pub fn resolve_one(
         let id_column = id_column.to_owned();
 
         FieldFuture::new(async move {
+            parse_at_block_argument(&ctx)?;
             let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
             let id: String =
                 extract::<String>(ctx.args.as_index_map(), &id_column.to_case(Case::Camel))?;
@@ -141,6 +216,50 @@ pub fn resolve_one(
     .argument(argument)
 }
 
+// Resolves the objects of the given ids in a single query, returns a list of the current object in
+// the order of the ids, with null for the unknown ids
+pub fn resolve_by_ids(
+    table_name: &str,
+    id_column: &str,
+    field_name: &str,
+    type_name: &str,
+    type_mapping: &TypeMapping,
+) -> Field {
+    let type_mapping = type_mapping.clone();
+    let table_name = table_name.to_owned();
+    let id_column = id_column.to_owned();
+    let argument_name = format!("{}s", id_column.to_case(Case::Camel));
+    let argument = InputValue::new(&argument_name, TypeRef::named_nn_list_nn(TypeRef::ID));
+
+    Field::new(field_name, TypeRef::named_list_nn(type_name), move |ctx| {
+        let type_mapping = type_mapping.clone();
+        let table_name = table_name.to_owned();
+        let id_column = id_column.to_owned();
+        let argument_name = argument_name.to_owned();
+
+        FieldFuture::new(async move {
+            parse_at_block_argument(&ctx)?;
+            let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+            let ids = extract::<Vec<String>>(ctx.args.as_index_map(), &argument_name)?;
+            let data = fetch_rows_by_ids(&mut conn, &table_name, &id_column, &ids).await?;
+
+            let mut objects = HashMap::new();
+            for row in &data {
+                let id: String = row.try_get(id_column.as_str())?;
+                objects.insert(id, value_mapping_from_row(row, &type_mapping, false)?);
+            }
+
+            let results = ids
+                .iter()
+                .map(|id| objects.get(id).cloned().map_or(Value::Null, Value::Object))
+                .collect();
+
+            Ok(Some(Value::List(results)))
+        })
+    })
+    .argument(argument)
+}
+
 // Resolves plural object queries, returns type of {type_name}Connection (eg "PlayerConnection")
 pub fn resolve_many(
     table_name: &str,
@@ -160,19 +279,46 @@ pub fn resolve_many(
             let id_column = id_column.to_owned();
 
             FieldFuture::new(async move {
+                parse_at_block_argument(&ctx)?;
                 let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
                 let connection = parse_connection_arguments(&ctx)?;
                 let keys = parse_keys_argument(&ctx)?;
-                let order = parse_order_argument(&ctx);
-                let total_count = count_rows(&mut conn, &table_name, &keys, &None).await?;
+                // entities ordered by a model member are selected along with the member
+                let (table_name, order) = match parse_entity_order_argument(&ctx)? {
+                    Some((model, order)) => {
+                        let table_name =
+                            entity_order_table(&table_name, model.as_deref(), &order.field)?;
+                        (table_name, Some(order))
+                    }
+                    None => (table_name, parse_order_argument(&ctx)),
+                };
+                let member_filters = parse_entity_where_argument(&ctx)?;
+                let key_ranges = parse_keys_range_argument(&ctx)?;
+                if let Some(position) =
+                    key_ranges.iter().flatten().map(|range| range.position).max()
+                {
+                    let max_key_count = fetch_max_key_count(&mut conn).await?;
+                    if position as i64 >= max_key_count {
+                        return Err(format!(
+                            "The key position {} is out of range, the models have at most {} keys.",
+                            position, max_key_count
+                        )
+                        .into());
+                    }
+                }
+                let total_count =
+                    count_rows(&mut conn, &table_name, &keys, &key_ranges, &None, &member_filters)
+                        .await?;
 
                 let (data, page_info) = fetch_multiple_rows(
                     &mut conn,
                     &table_name,
                     &id_column,
                     &keys,
+                    &key_ranges,
                     &order,
                     &None,
+                    &member_filters,
                     &connection,
                     total_count,
                 )
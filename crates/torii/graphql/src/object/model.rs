@@ -1,23 +1,34 @@
 use async_graphql::dynamic::indexmap::IndexMap;
 use async_graphql::dynamic::{
-    Enum, Field, InputObject, InputValue, SubscriptionField, SubscriptionFieldFuture, TypeRef,
+    Enum, Field, FieldFuture, InputObject, InputValue, SubscriptionField, SubscriptionFieldFuture,
+    TypeRef,
 };
 use async_graphql::{Name, Value};
+use sqlx::{Pool, Sqlite};
 use tokio_stream::StreamExt;
 use torii_core::simple_broker::SimpleBroker;
 use torii_core::types::Model;
 
 use super::{resolve_many, BasicObject, ResolvableObject, TypeMapping, ValueMapping};
 use crate::constants::{
-    DATETIME_FORMAT, ID_COLUMN, MODEL_NAMES, MODEL_ORDER_FIELD_TYPE_NAME, MODEL_ORDER_TYPE_NAME,
+    DATETIME_FORMAT, ID_COLUMN, MODEL_FIELD_KIND_TYPE_NAME, MODEL_FIELD_NAMES,
+    MODEL_FIELD_TYPE_NAME, MODEL_NAMES, MODEL_ORDER_FIELD_TYPE_NAME, MODEL_ORDER_TYPE_NAME,
     MODEL_TABLE, MODEL_TYPE_NAME, ORDER_ASC, ORDER_DESC, ORDER_DIR_TYPE_NAME,
 };
-use crate::mapping::MODEL_TYPE_MAPPING;
+use crate::mapping::{MODEL_FIELD_TYPE_MAPPING, MODEL_TYPE_MAPPING};
 use crate::object::resolve_one;
+use crate::query::type_mapping_query;
+use crate::types::{GraphqlType, TypeData};
+use crate::utils::extract;
 
 const ORDER_BY_NAME: &str = "NAME";
 const ORDER_BY_HASH: &str = "CLASS_HASH";
 
+const FIELD_KIND_SCALAR: &str = "SCALAR";
+const FIELD_KIND_NESTED: &str = "NESTED";
+const FIELD_KIND_LIST: &str = "LIST";
+const FIELD_KIND_UNION: &str = "UNION";
+
 pub struct ModelObject;
 
 impl BasicObject for ModelObject {
@@ -47,8 +58,13 @@ impl ResolvableObject for ModelObject {
         let direction = Enum::new(ORDER_DIR_TYPE_NAME).item(ORDER_ASC).item(ORDER_DESC);
         let field_order =
             Enum::new(MODEL_ORDER_FIELD_TYPE_NAME).item(ORDER_BY_NAME).item(ORDER_BY_HASH);
+        let field_kind = Enum::new(MODEL_FIELD_KIND_TYPE_NAME)
+            .item(FIELD_KIND_SCALAR)
+            .item(FIELD_KIND_NESTED)
+            .item(FIELD_KIND_LIST)
+            .item(FIELD_KIND_UNION);
 
-        Some(vec![direction, field_order])
+        Some(vec![direction, field_order, field_kind])
     }
 
     fn resolvers(&self) -> Vec<Field> {
@@ -70,12 +86,14 @@ impl ResolvableObject for ModelObject {
         resolve_many =
             resolve_many.argument(InputValue::new("order", TypeRef::named(MODEL_ORDER_TYPE_NAME)));
 
-        vec![resolve_one, resolve_many]
+        vec![resolve_one, resolve_many, model_schema_field()]
     }
 
     fn subscriptions(&self) -> Option<Vec<SubscriptionField>> {
-        Some(vec![
-            SubscriptionField::new("modelRegistered", TypeRef::named_nn(self.type_name()), |ctx| {
+        Some(vec![SubscriptionField::new(
+            "modelRegistered",
+            TypeRef::named_nn(self.type_name()),
+            |ctx| {
                 {
                     SubscriptionFieldFuture::new(async move {
                         let id = match ctx.args.get("id") {
@@ -94,9 +112,9 @@ impl ResolvableObject for ModelObject {
                         }))
                     })
                 }
-            })
-            .argument(InputValue::new("id", TypeRef::named(TypeRef::ID))),
-        ])
+            },
+        )
+        .argument(InputValue::new("id", TypeRef::named(TypeRef::ID)))])
     }
 }
 
@@ -119,3 +137,74 @@ impl ModelObject {
         ])
     }
 }
+
+// Fields of a model, along with their type and the kind of data they hold
+pub struct ModelFieldObject;
+
+impl BasicObject for ModelFieldObject {
+    fn name(&self) -> (&str, &str) {
+        MODEL_FIELD_NAMES
+    }
+
+    fn type_name(&self) -> &str {
+        MODEL_FIELD_TYPE_NAME
+    }
+
+    fn type_mapping(&self) -> &TypeMapping {
+        &MODEL_FIELD_TYPE_MAPPING
+    }
+}
+
+impl ModelFieldObject {
+    pub fn value_mapping(name: &Name, type_data: &TypeData) -> ValueMapping {
+        IndexMap::from([
+            (Name::new("name"), Value::from(name.to_string())),
+            (Name::new("type"), Value::from(type_data.type_ref().to_string())),
+            (Name::new("kind"), Value::Enum(Name::new(field_kind(type_data)))),
+        ])
+    }
+}
+
+// Enums are unions of their variants, whether they hold data or not
+fn field_kind(type_data: &TypeData) -> &'static str {
+    let enum_type = TypeData::Simple(TypeRef::named(GraphqlType::Enum.to_string()));
+
+    match type_data {
+        TypeData::Simple(_) if type_data == &enum_type => FIELD_KIND_UNION,
+        TypeData::Simple(_) => FIELD_KIND_SCALAR,
+        TypeData::Enum(_) => FIELD_KIND_UNION,
+        TypeData::Nested(_) => FIELD_KIND_NESTED,
+        TypeData::List(_) => FIELD_KIND_LIST,
+        TypeData::Option(inner) => field_kind(inner),
+    }
+}
+
+fn model_schema_field() -> Field {
+    Field::new("modelSchema", TypeRef::named_nn_list(MODEL_FIELD_TYPE_NAME), |ctx| {
+        FieldFuture::new(async move {
+            let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+            let name = extract::<String>(ctx.args.as_index_map(), "name")?;
+
+            let model_id: Option<String> =
+                sqlx::query_scalar("SELECT id FROM models WHERE name = ?")
+                    .bind(&name)
+                    .fetch_optional(&mut *conn)
+                    .await?;
+            let Some(model_id) = model_id else {
+                return Ok(None);
+            };
+
+            let type_mapping = type_mapping_query(&mut conn, &model_id).await?;
+
+            Ok(Some(Value::List(
+                type_mapping
+                    .iter()
+                    .map(|(name, type_data)| {
+                        Value::Object(ModelFieldObject::value_mapping(name, type_data))
+                    })
+                    .collect(),
+            )))
+        })
+    })
+    .argument(InputValue::new("name", TypeRef::named_nn(TypeRef::STRING)))
+}
@@ -1,23 +1,32 @@
+use std::collections::HashMap;
+use std::io::Write;
 use std::ops::Deref;
+use std::sync::Arc;
 
-use async_graphql::dynamic::{Enum, Field, FieldFuture, InputObject, Object, TypeRef};
-use async_graphql::Value;
+use async_graphql::dynamic::{Enum, Field, FieldFuture, InputObject, InputValue, Object, TypeRef};
+use async_graphql::{Error, ErrorExtensions, Name, Value};
+use base64::engine::general_purpose;
+use base64::Engine as _;
 use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::Deserialize;
 use sqlx::{FromRow, Pool, Sqlite};
 
 use super::connection::{connection_arguments, connection_output, parse_connection_arguments};
+use super::entity::model_list_element_query;
 use super::inputs::order_input::{order_argument, parse_order_argument, OrderInputObject};
 use super::inputs::where_input::{parse_where_argument, where_argument, WhereInputObject};
 use super::inputs::InputObjectTrait;
 use super::{BasicObject, ResolvableObject, TypeMapping, ValueMapping};
 use crate::constants::{
-    ENTITY_ID_COLUMN, ENTITY_TABLE, EVENT_ID_COLUMN, ID_COLUMN, INTERNAL_ENTITY_ID_KEY,
+    COMPRESSED_LIST_PREFIX, ENTITY_ID_COLUMN, ENTITY_TABLE, EVENT_ID_COLUMN, ID_COLUMN,
+    INTERNAL_ENTITY_ID_KEY,
 };
 use crate::mapping::ENTITY_TYPE_MAPPING;
 use crate::query::data::{count_rows, fetch_multiple_rows, fetch_single_row};
-use crate::query::value_mapping_from_row;
-use crate::types::TypeData;
+use crate::query::{option_type_mapping, option_value, value_mapping_from_row};
+use crate::types::{FieldCasing, ListBatchSize, ListCompression, TypeData};
 use crate::utils::extract;
 
 #[derive(FromRow, Deserialize, PartialEq, Eq, Debug)]
@@ -34,21 +43,81 @@ pub struct ModelMember {
     pub created_at: DateTime<Utc>,
 }
 
+// Computes the value of a field from the values of the other fields of its model
+pub type ComputedFieldResolver = Arc<dyn Fn(&ValueMapping) -> Value + Send + Sync>;
+
+// A field added to a model type, whose value is derived from the model data rather than stored
+#[derive(Clone)]
+pub struct ComputedField {
+    pub name: String,
+    pub type_ref: TypeRef,
+    pub resolver: ComputedFieldResolver,
+}
+
+// The computed fields of the models, by model name, which are added to the model types when the
+// schema is built
+#[derive(Clone, Default)]
+pub struct ComputedFields(HashMap<String, Vec<ComputedField>>);
+
+impl ComputedFields {
+    pub fn register<F>(
+        &mut self,
+        model: &str,
+        field: &str,
+        type_ref: TypeRef,
+        resolver: F,
+    ) -> &mut Self
+    where
+        F: Fn(&ValueMapping) -> Value + Send + Sync + 'static,
+    {
+        self.0.entry(model.to_string()).or_default().push(ComputedField {
+            name: field.to_string(),
+            type_ref,
+            resolver: Arc::new(resolver),
+        });
+        self
+    }
+
+    pub fn fields(&self, model: &str) -> &[ComputedField] {
+        self.0.get(model).map_or(&[], |fields| fields.as_slice())
+    }
+}
+
 pub struct ModelDataObject {
     pub name: String,
     pub plural_name: String,
     pub type_name: String,
     pub type_mapping: TypeMapping,
+    pub computed_fields: Vec<ComputedField>,
+    pub field_casing: FieldCasing,
+    pub list_compression: ListCompression,
     pub where_input: WhereInputObject,
     pub order_input: OrderInputObject,
 }
 
 impl ModelDataObject {
-    pub fn new(name: String, type_name: String, type_mapping: TypeMapping) -> Self {
-        let where_input = WhereInputObject::new(type_name.as_str(), &type_mapping);
+    pub fn new(
+        name: String,
+        type_name: String,
+        type_mapping: TypeMapping,
+        computed_fields: Vec<ComputedField>,
+        field_casing: FieldCasing,
+        list_compression: ListCompression,
+    ) -> Self {
+        let where_input = WhereInputObject::new(type_name.as_str(), &type_mapping, field_casing);
         let order_input = OrderInputObject::new(type_name.as_str(), &type_mapping);
         let plural_name = format!("{}Models", name);
-        Self { name, plural_name, type_name, type_mapping, where_input, order_input }
+        Self {
+            name,
+            plural_name,
+            type_name,
+            type_mapping,
+            computed_fields,
+            field_casing,
+            list_compression,
+            where_input,
+            order_input,
+        }
     }
 }
 
@@ -69,12 +138,20 @@ impl BasicObject for ModelDataObject {
         let mut objects = data_objects_recursion(
             self.type_name(),
             self.type_mapping(),
+            false,
+            false,
             vec![self.type_name().to_string()],
+            self.field_casing,
+            self.list_compression,
         );
 
         // root object requires entity_field association
         let mut root = objects.pop().unwrap();
-        root = root.field(entity_field());
+        root = root.field(entity_field()).field(entity_id_field());
+
+        for computed_field in &self.computed_fields {
+            root = root.field(computed_field_resolver(computed_field.clone()));
+        }
 
         objects.push(root);
         objects
@@ -94,6 +171,7 @@ impl ResolvableObject for ModelDataObject {
         let type_name = self.type_name.clone();
         let type_mapping = self.type_mapping.clone();
         let where_mapping = self.where_input.type_mapping.clone();
+        let field_casing = self.field_casing;
         let field_type = format!("{}Connection", self.type_name());
 
         let mut field = Field::new(self.name().1, TypeRef::named(field_type), move |ctx| {
@@ -104,17 +182,20 @@ impl ResolvableObject for ModelDataObject {
             FieldFuture::new(async move {
                 let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
                 let order = parse_order_argument(&ctx);
-                let filters = parse_where_argument(&ctx, &where_mapping)?;
+                let filters = parse_where_argument(&ctx, &where_mapping, field_casing)?;
                 let connection = parse_connection_arguments(&ctx)?;
 
-                let total_count = count_rows(&mut conn, &type_name, &None, &filters).await?;
+                let total_count =
+                    count_rows(&mut conn, &type_name, &None, &None, &filters, &None).await?;
                 let (data, page_info) = fetch_multiple_rows(
                     &mut conn,
                     &type_name,
                     EVENT_ID_COLUMN,
                     &None,
+                    &None,
                     &order,
                     &filters,
+                    &None,
                     &connection,
                     total_count,
                 )
@@ -170,29 +251,71 @@ impl ResolvableObject for ModelDataObject {
 //     objects
 // }
 
+// The objects of the types nested in a list are flagged, as their lists are nested in more than one
 fn data_objects_recursion(
     type_name: &str,
     type_mapping: &TypeMapping,
+    is_enum: bool,
+    in_list: bool,
     path_array: Vec<String>,
+    field_casing: FieldCasing,
+    list_compression: ListCompression,
 ) -> Vec<Object> {
     let mut objects: Vec<Object> = type_mapping
         .iter()
         .filter_map(|(field_name, type_data)| {
-            if let TypeData::Nested((nested_type, nested_mapping)) = type_data {
+            if let TypeData::Nested((nested_type, nested_mapping))
+            | TypeData::Enum((nested_type, nested_mapping)) = type_data
+            {
                 let mut nested_path = path_array.clone();
                 nested_path.push(field_name.to_string());
-                let nested_objects =
-                    data_objects_recursion(&nested_type.to_string(), nested_mapping, nested_path);
+                let nested_objects = data_objects_recursion(
+                    &nested_type.to_string(),
+                    nested_mapping,
+                    type_data.is_enum(),
+                    in_list,
+                    nested_path,
+                    field_casing,
+                    list_compression,
+                );
 
                 Some(nested_objects)
             } else if let TypeData::List(inner) = type_data {
-                if let TypeData::Nested((nested_type, nested_mapping)) = inner.deref() {
+                if let TypeData::Nested((nested_type, nested_mapping))
+                | TypeData::Enum((nested_type, nested_mapping)) = inner.deref()
+                {
                     let mut nested_path = path_array.clone();
                     nested_path.push(inner.type_ref().to_string());
                     let nested_objects = data_objects_recursion(
                         &nested_type.to_string(),
                         nested_mapping,
+                        inner.is_enum(),
+                        true,
                         nested_path,
+                        field_casing,
+                        list_compression,
+                    );
+
+                    return Some(nested_objects);
+                }
+
+                None
+            } else if let TypeData::Option(inner) = type_data {
+                // the value of an optional struct is stored in the table of the `Some` variant
+                if let TypeData::Nested((nested_type, nested_mapping))
+                | TypeData::Enum((nested_type, nested_mapping)) = inner.deref()
+                {
+                    let mut nested_path = path_array.clone();
+                    nested_path.push(field_name.to_string());
+                    nested_path.push("Some".to_string());
+                    let nested_objects = data_objects_recursion(
+                        &nested_type.to_string(),
+                        nested_mapping,
+                        inner.is_enum(),
+                        in_list,
+                        nested_path,
+                        field_casing,
+                        list_compression,
                     );
 
                     return Some(nested_objects);
@@ -206,17 +329,56 @@ fn data_objects_recursion(
         .flatten()
         .collect();
 
-    objects.push(object(type_name, type_mapping, path_array));
+    objects.push(object(
+        type_name,
+        type_mapping,
+        is_enum,
+        in_list,
+        path_array,
+        field_casing,
+        list_compression,
+    ));
     objects
 }
 
-pub fn object(type_name: &str, type_mapping: &TypeMapping, path_array: Vec<String>) -> Object {
+// The fields are named with the casing of the schema, their resolvers looking up the model data by
+// the names of the members. The fields of an enum other than `option` are its variants. The lists
+// which are not nested in another list come with an `{name}_at` field, fetching a single element,
+// and the lists of scalars with a `{name}_compressed` field when the lists are compressed
+pub fn object(
+    type_name: &str,
+    type_mapping: &TypeMapping,
+    is_enum: bool,
+    in_list: bool,
+    path_array: Vec<String>,
+    field_casing: FieldCasing,
+    list_compression: ListCompression,
+) -> Object {
     let mut object = Object::new(type_name);
 
     for (field_name, type_data) in type_mapping.clone() {
         let path_array = path_array.clone();
 
-        let field = Field::new(field_name.to_string(), type_data.type_ref(), move |ctx| {
+        let element_field = match &type_data {
+            TypeData::List(inner) if !in_list => {
+                let mut list_path = path_array.clone();
+                list_path.push(field_name.to_string());
+                let name = field_casing.apply(&format!("{}_at", field_name));
+                Some(list_element_field(name, *inner.clone(), list_path))
+            }
+            _ => None,
+        };
+        let compressed_field = match (&type_data, list_compression) {
+            (TypeData::List(inner), ListCompression(Some(threshold))) if inner.is_simple() => {
+                let name = field_casing.apply(&format!("{}_compressed", field_name));
+                Some(compressed_list_field(name, field_name.clone(), threshold))
+            }
+            _ => None,
+        };
+        let is_variant = is_enum && field_name.as_str() != "option";
+        let name = field_casing.apply(&field_name);
+
+        let field = Field::new(name, type_data.type_ref(), move |ctx| {
             let field_name = field_name.clone();
             let type_data = type_data.clone();
             let mut path_array = path_array.clone();
@@ -228,8 +390,15 @@ pub fn object(type_name: &str, type_mapping: &TypeMapping, path_array: Vec<Strin
 
             return FieldFuture::new(async move {
                 if let Some(value) = ctx.parent_value.as_value() {
+                    // Only the active variant of an enum is resolved, along with its name in
+                    // `option`. The tables of the other variants may have no row, or still hold
+                    // the data of a variant which was previously active
+                    if is_variant && !is_active_variant(value, &field_name) {
+                        return Ok(None);
+                    }
+
                     // Nested types resolution
-                    if let TypeData::Nested((_, nested_mapping)) = type_data {
+                    if let Some(nested_mapping) = type_data.type_mapping() {
                         return match ctx.parent_value.try_to_value()? {
                             Value::Object(indexmap) => {
                                 let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
@@ -249,7 +418,7 @@ pub fn object(type_name: &str, type_mapping: &TypeMapping, path_array: Vec<Strin
                                     &entity_id,
                                 )
                                 .await?;
-                                let result = value_mapping_from_row(&data, &nested_mapping, true)?;
+                                let result = value_mapping_from_row(&data, nested_mapping, true)?;
 
                                 Ok(Some(Value::Object(result)))
                             }
@@ -257,6 +426,54 @@ pub fn object(type_name: &str, type_mapping: &TypeMapping, path_array: Vec<Strin
                         };
                     }
 
+                    // Option types resolution
+                    if let TypeData::Option(inner) = type_data {
+                        return match ctx.parent_value.try_to_value()? {
+                            Value::Object(indexmap) => {
+                                let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+                                let entity_id =
+                                    extract::<String>(indexmap, INTERNAL_ENTITY_ID_KEY)?;
+
+                                // if we already fetched our model data, return it
+                                if let Some(data) = indexmap.get(&field_name) {
+                                    return Ok(Some(data.clone()));
+                                }
+
+                                let data = fetch_single_row(
+                                    &mut conn,
+                                    &table_name,
+                                    ENTITY_ID_COLUMN,
+                                    &entity_id,
+                                )
+                                .await?;
+                                let option_mapping = option_type_mapping(&inner);
+                                let mut result =
+                                    value_mapping_from_row(&data, &option_mapping, true)?;
+                                let is_some =
+                                    result.get(&Name::new("option")) == Some(&Value::from("Some"));
+
+                                // the fields of an optional struct are in the `Some` variant table
+                                if let (true, Some(nested_mapping)) =
+                                    (is_some, inner.type_mapping())
+                                {
+                                    let data = fetch_single_row(
+                                        &mut conn,
+                                        &format!("{}$Some", table_name),
+                                        ENTITY_ID_COLUMN,
+                                        &entity_id,
+                                    )
+                                    .await?;
+                                    let nested =
+                                        value_mapping_from_row(&data, nested_mapping, true)?;
+                                    result.insert(Name::new("Some"), Value::Object(nested));
+                                }
+
+                                Ok(Some(option_value(Value::Object(result))))
+                            }
+                            _ => Err("incorrect value, requires Value::Object".into()),
+                        };
+                    }
+
                     // Simple types resolution
                     return match value {
                         Value::Object(value_mapping) => {
@@ -267,9 +484,11 @@ pub fn object(type_name: &str, type_mapping: &TypeMapping, path_array: Vec<Strin
                 }
 
                 // Catch model union resolutions, async-graphql sends union types as IndexMap<Name,
-                // ConstValue>
+                // ConstValue>. The fields missing from the data (left out of a delta) are null
                 if let Some(value_mapping) = ctx.parent_value.downcast_ref::<ValueMapping>() {
-                    return Ok(Some(value_mapping.get(&field_name).unwrap().clone()));
+                    return Ok(Some(
+                        value_mapping.get(&field_name).cloned().unwrap_or(Value::Null),
+                    ));
                 }
 
                 Err("Field resolver only accepts Value or IndexMap".into())
@@ -277,11 +496,146 @@ pub fn object(type_name: &str, type_mapping: &TypeMapping, path_array: Vec<Strin
         });
 
         object = object.field(field);
+        if let Some(element_field) = element_field {
+            object = object.field(element_field);
+        }
+        if let Some(compressed_field) = compressed_field {
+            object = object.field(compressed_field);
+        }
     }
 
     object
 }
 
+fn is_active_variant(value: &Value, variant: &str) -> bool {
+    match value {
+        Value::Object(value_mapping) => {
+            value_mapping.get(&Name::new("option")) == Some(&Value::from(variant))
+        }
+        _ => false,
+    }
+}
+
+// The element at `index` of a list, fetched alone rather than along with the whole list, or null
+// if the index is out of range
+fn list_element_field(name: String, inner: TypeData, path_array: Vec<String>) -> Field {
+    Field::new(name, inner.type_ref(), move |ctx| {
+        let inner = inner.clone();
+        let path_array = path_array.clone();
+
+        FieldFuture::new(async move {
+            let index = ctx.args.try_get("index")?.i64()?;
+            if index < 0 {
+                return Err("The index of a list element must be non-negative".into());
+            }
+
+            // the parent holds the model data, as a value for the model queries or as a value
+            // mapping for the models of an entity
+            let entity_id = match ctx.parent_value.as_value() {
+                Some(Value::Object(value_mapping)) => {
+                    extract::<String>(value_mapping, INTERNAL_ENTITY_ID_KEY)?
+                }
+                _ => match ctx.parent_value.downcast_ref::<ValueMapping>() {
+                    Some(value_mapping) => {
+                        extract::<String>(value_mapping, INTERNAL_ENTITY_ID_KEY)?
+                    }
+                    None => return Err("Field resolver only accepts Value or IndexMap".into()),
+                },
+            };
+
+            let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+            let ListBatchSize(batch_size) =
+                ctx.data_opt::<ListBatchSize>().copied().unwrap_or_default();
+            let element = model_list_element_query(
+                &mut conn, path_array, &entity_id, index, &inner, batch_size,
+            )
+            .await
+            .map_err(|error| error.extend())?;
+
+            Ok(Some(element))
+        })
+    })
+    .argument(InputValue::new("index", TypeRef::named_nn(TypeRef::INT)))
+}
+
+// The JSON of a list of scalars, as a string. The lists larger than the threshold of the schema
+// are compressed: `COMPRESSED_LIST_PREFIX` followed by their gzipped JSON encoded in base64
+fn compressed_list_field(name: String, field_name: Name, threshold: usize) -> Field {
+    Field::new(name, TypeRef::named(TypeRef::STRING), move |ctx| {
+        let field_name = field_name.clone();
+
+        FieldFuture::new(async move {
+            // the parent holds the model data, as a value for the model queries or as a value
+            // mapping for the models of an entity. The lists missing from the data (left out of a
+            // delta) are null
+            let value = match ctx.parent_value.as_value() {
+                Some(Value::Object(value_mapping)) => value_mapping.get(&field_name).cloned(),
+                _ => match ctx.parent_value.downcast_ref::<ValueMapping>() {
+                    Some(value_mapping) => value_mapping.get(&field_name).cloned(),
+                    None => return Err("Field resolver only accepts Value or IndexMap".into()),
+                },
+            };
+
+            match value {
+                Some(value @ Value::List(_)) => {
+                    Ok(Some(Value::from(compress_list(&value, threshold)?)))
+                }
+                _ => Ok(None),
+            }
+        })
+    })
+}
+
+fn compress_list(value: &Value, threshold: usize) -> Result<String, Error> {
+    let json = serde_json::to_string(value)?;
+    if json.len() <= threshold {
+        return Ok(json);
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(json.as_bytes())?;
+    let compressed = general_purpose::STANDARD.encode(encoder.finish()?);
+
+    Ok(format!("{}{}", COMPRESSED_LIST_PREFIX, compressed))
+}
+
+// The parent of a computed field holds the model data, as a value for the model queries or as a
+// value mapping for the models of an entity
+fn computed_field_resolver(computed_field: ComputedField) -> Field {
+    let resolver = computed_field.resolver;
+
+    Field::new(computed_field.name, computed_field.type_ref, move |ctx| {
+        let resolver = resolver.clone();
+
+        FieldFuture::new(async move {
+            if let Some(Value::Object(value_mapping)) = ctx.parent_value.as_value() {
+                return Ok(Some(resolver(value_mapping)));
+            }
+
+            if let Some(value_mapping) = ctx.parent_value.downcast_ref::<ValueMapping>() {
+                return Ok(Some(resolver(value_mapping)));
+            }
+
+            Err("Field resolver only accepts Value or IndexMap".into())
+        })
+    })
+}
+
+// The id of the entity of the model, available without fetching the entity
+fn entity_id_field() -> Field {
+    Field::new("entityId", TypeRef::named_nn(TypeRef::ID), |ctx| {
+        FieldFuture::new(async move {
+            match ctx.parent_value.try_to_value()? {
+                Value::Object(indexmap) => {
+                    let entity_id = extract::<String>(indexmap, INTERNAL_ENTITY_ID_KEY)?;
+                    Ok(Some(Value::from(entity_id)))
+                }
+                _ => Err("incorrect value, requires Value::Object".into()),
+            }
+        })
+    })
+}
+
 fn entity_field() -> Field {
     Field::new("entity", TypeRef::named("World__Entity"), |ctx| {
         FieldFuture::new(async move {
@@ -2,28 +2,98 @@ use async_graphql::connection::PageInfo;
 use sqlx::sqlite::SqliteRow;
 use sqlx::{Result, Row, SqliteConnection};
 
-use super::filter::{Filter, FilterValue};
+use super::filter::{Filter, FilterValue, KeyRange, MemberFilter};
 use super::order::{CursorDirection, Direction, Order};
-use crate::constants::{DEFAULT_LIMIT, MODEL_TABLE};
+use super::validate_identifier;
+use crate::constants::{
+    DEFAULT_LIMIT, ENTITY_ID_COLUMN, ENTITY_MODEL_TABLE, ENTITY_TABLE, EVENT_ID_COLUMN, ID_COLUMN,
+    MODEL_TABLE,
+};
 use crate::object::connection::{cursor, ConnectionArguments};
 
 pub async fn count_rows(
     conn: &mut SqliteConnection,
     table_name: &str,
     keys: &Option<Vec<String>>,
+    key_ranges: &Option<Vec<KeyRange>>,
     filters: &Option<Vec<Filter>>,
+    member_filters: &Option<Vec<MemberFilter>>,
 ) -> Result<i64> {
     let mut query = format!("SELECT COUNT(*) FROM {}", table_name);
-    let conditions = build_conditions(keys, filters);
+    let (conditions, values) = build_filter_conditions(keys, key_ranges, filters, member_filters)?;
 
     if !conditions.is_empty() {
         query.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
     }
 
-    let result: (i64,) = sqlx::query_as(&query).fetch_one(conn).await?;
+    let mut statement = sqlx::query_as::<_, (i64,)>(&query);
+    for value in &values {
+        statement = statement.bind(value);
+    }
+
+    let result = statement.fetch_one(conn).await?;
     Ok(result.0)
 }
 
+// Aggregates of the entities: their count, and the minimum, maximum, sum and average of a member of
+// their model if any. With a model, only the entities having it are aggregated
+pub async fn fetch_aggregates(
+    conn: &mut SqliteConnection,
+    model: Option<&str>,
+    member: Option<&str>,
+) -> Result<SqliteRow> {
+    let Some(model) = model else {
+        let query = format!(
+            "SELECT COUNT(*) AS count, NULL AS min, NULL AS max, NULL AS sum, NULL AS avg FROM {}",
+            ENTITY_TABLE
+        );
+        return sqlx::query(&query).fetch_one(conn).await;
+    };
+    validate_identifier(model)?;
+
+    let aggregates = match member {
+        Some(member) => {
+            validate_identifier(member)?;
+            let column = format!("[{model}].external_{member}");
+            format!(
+                "MIN({column}) AS min, MAX({column}) AS max, SUM({column}) AS sum, AVG({column}) \
+                 AS avg"
+            )
+        }
+        None => "NULL AS min, NULL AS max, NULL AS sum, NULL AS avg".to_string(),
+    };
+
+    let query = format!(
+        "SELECT COUNT(*) AS count, {aggregates} FROM {ENTITY_TABLE} JOIN [{model}] ON \
+         [{model}].{ENTITY_ID_COLUMN} = {ENTITY_TABLE}.{ID_COLUMN}"
+    );
+    sqlx::query(&query).fetch_one(conn).await
+}
+
+// Type of a member at the root of a model, if the model has it
+pub async fn fetch_member_type(
+    conn: &mut SqliteConnection,
+    model: &str,
+    member: &str,
+) -> Result<Option<String>> {
+    let query = format!(
+        "SELECT type FROM model_members WHERE model_idx = 0 AND name = ? AND model_id = (SELECT \
+         id FROM {} WHERE name = ?)",
+        MODEL_TABLE
+    );
+    let res: Option<(String,)> =
+        sqlx::query_as(&query).bind(member).bind(model).fetch_optional(conn).await?;
+    Ok(res.map(|(ty,)| ty))
+}
+
+// Number of keys of the model having the most keys
+pub async fn fetch_max_key_count(conn: &mut SqliteConnection) -> Result<i64> {
+    let query = "SELECT COALESCE(MAX(key_count), 0) FROM (SELECT COUNT(*) AS key_count FROM \
+                 model_members WHERE key = 1 GROUP BY model_id)";
+    let res: (i64,) = sqlx::query_as(query).fetch_one(conn).await?;
+    Ok(res.0)
+}
+
 pub async fn fetch_world_address(conn: &mut SqliteConnection) -> Result<String> {
     let query = "SELECT world_address FROM worlds".to_string();
     let res: (String,) = sqlx::query_as(&query).fetch_one(conn).await?;
@@ -40,18 +110,62 @@ pub async fn fetch_single_row(
     sqlx::query(&query).fetch_one(conn).await
 }
 
+pub async fn fetch_rows_by_ids(
+    conn: &mut SqliteConnection,
+    table_name: &str,
+    id_column: &str,
+    ids: &[String],
+) -> Result<Vec<SqliteRow>> {
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let query = format!("SELECT * FROM {} WHERE {} IN ({})", table_name, id_column, placeholders);
+
+    let mut statement = sqlx::query(&query);
+    for id in ids {
+        statement = statement.bind(id);
+    }
+
+    statement.fetch_all(conn).await
+}
+
+// Entities having all the given models (and possibly others), the most recently updated first.
+// The models are looked up by name, which must not be repeated
+pub async fn fetch_entities_with_models(
+    conn: &mut SqliteConnection,
+    models: &[String],
+    limit: u64,
+) -> Result<Vec<SqliteRow>> {
+    let placeholders = vec!["?"; models.len()].join(", ");
+    let query = format!(
+        "SELECT * FROM {ENTITY_TABLE} WHERE {ID_COLUMN} IN (SELECT {ENTITY_ID_COLUMN} FROM \
+         {ENTITY_MODEL_TABLE} JOIN {MODEL_TABLE} ON {MODEL_TABLE}.{ID_COLUMN} = \
+         {ENTITY_MODEL_TABLE}.model_id WHERE {MODEL_TABLE}.name IN ({placeholders}) GROUP BY \
+         {ENTITY_ID_COLUMN} HAVING COUNT(DISTINCT {MODEL_TABLE}.name) = ?) ORDER BY \
+         {EVENT_ID_COLUMN} DESC LIMIT ?"
+    );
+
+    let mut statement = sqlx::query(&query);
+    for model in models {
+        statement = statement.bind(model);
+    }
+
+    statement.bind(models.len() as i64).bind(limit as i64).fetch_all(conn).await
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn fetch_multiple_rows(
     conn: &mut SqliteConnection,
     table_name: &str,
     id_column: &str,
     keys: &Option<Vec<String>>,
+    key_ranges: &Option<Vec<KeyRange>>,
     order: &Option<Order>,
     filters: &Option<Vec<Filter>>,
+    member_filters: &Option<Vec<MemberFilter>>,
     connection: &ConnectionArguments,
     total_count: i64,
 ) -> Result<(Vec<SqliteRow>, PageInfo)> {
-    let mut conditions = build_conditions(keys, filters);
+    let (mut conditions, values) =
+        build_filter_conditions(keys, key_ranges, filters, member_filters)?;
 
     let mut cursor_param = &connection.after;
     if let Some(after_cursor) = &connection.after {
@@ -113,7 +227,12 @@ pub async fn fetch_multiple_rows(
         query.push_str(&format!(" OFFSET {}", offset));
     }
 
-    let mut data = sqlx::query(&query).fetch_all(conn).await?;
+    let mut statement = sqlx::query(&query);
+    for value in &values {
+        statement = statement.bind(value);
+    }
+
+    let mut data = statement.fetch_all(conn).await?;
     let mut page_info = PageInfo {
         has_previous_page: false,
         has_next_page: false,
@@ -180,6 +299,26 @@ pub async fn fetch_multiple_rows(
     }
 }
 
+// Entities are ordered by a model member through the external column of its model table, selected
+// along with the entity so that it is ordered and paginated by cursors as model data is. Only the
+// entities having the model are selected. Without model, the entities are ordered by one of their
+// own columns, selected as an external column likewise
+pub fn entity_order_table(table_name: &str, model: Option<&str>, member: &str) -> Result<String> {
+    validate_identifier(member)?;
+
+    let Some(model) = model else {
+        return Ok(format!(
+            "(SELECT {table_name}.*, {table_name}.{member} AS external_{member} FROM {table_name})"
+        ));
+    };
+    validate_identifier(model)?;
+
+    Ok(format!(
+        "(SELECT {table_name}.*, [{model}].external_{member} FROM {table_name} JOIN [{model}] ON \
+         [{model}].{ENTITY_ID_COLUMN} = {table_name}.{ID_COLUMN})"
+    ))
+}
+
 fn handle_cursor(
     cursor: &str,
     order: &Option<Order>,
@@ -204,10 +343,28 @@ fn handle_cursor(
             }
             None => Ok(format!("{} {} '{}'", id_column, direction.as_ref(), event_id)),
         },
-        Err(_) => Err(sqlx::Error::Decode("Invalid cursor format".into())),
+        Err(error) => Err(sqlx::Error::Decode(error.message.into())),
     }
 }
 
+// Conditions of the filters of a query and the values bound to them, shared by the count and the
+// fetch of the rows so that the total count always matches the filtered rows being paginated
+fn build_filter_conditions(
+    keys: &Option<Vec<String>>,
+    key_ranges: &Option<Vec<KeyRange>>,
+    filters: &Option<Vec<Filter>>,
+    member_filters: &Option<Vec<MemberFilter>>,
+) -> Result<(Vec<String>, Vec<String>)> {
+    let mut conditions = build_conditions(keys, filters);
+    let (member_conditions, mut values) = build_member_conditions(member_filters)?;
+    conditions.extend(member_conditions);
+    let (key_range_conditions, key_range_values) = build_key_range_conditions(key_ranges);
+    conditions.extend(key_range_conditions);
+    values.extend(key_range_values);
+
+    Ok((conditions, values))
+}
+
 fn build_conditions(keys: &Option<Vec<String>>, filters: &Option<Vec<Filter>>) -> Vec<String> {
     let mut conditions = Vec::new();
 
@@ -245,6 +402,69 @@ fn build_conditions(keys: &Option<Vec<String>>, filters: &Option<Vec<Filter>>) -
     conditions
 }
 
+// Member filters select the entities through their model tables, the compared values are bound as
+// query parameters, and the table and column names are validated as they can't be bound
+fn build_member_conditions(
+    member_filters: &Option<Vec<MemberFilter>>,
+) -> Result<(Vec<String>, Vec<String>)> {
+    let mut conditions = Vec::new();
+    let mut values = Vec::new();
+
+    if let Some(member_filters) = member_filters {
+        for filter in member_filters {
+            validate_identifier(&filter.model)?;
+            validate_identifier(&filter.member)?;
+
+            conditions.push(format!(
+                "{ID_COLUMN} IN (SELECT {ENTITY_ID_COLUMN} FROM [{}] WHERE external_{} {} ?)",
+                filter.model, filter.member, filter.comparator
+            ));
+            values.push(filter.value.clone());
+        }
+    }
+
+    Ok((conditions, values))
+}
+
+// Keys are stored as a single string of hex felts separated by `/`, the key at a given position is
+// extracted by turning this string into a JSON array. As the felts are stored without leading
+// zeros, they are compared as numbers once left padded to the length of the largest felt, the
+// bounds being bound as query parameters
+fn build_key_range_conditions(key_ranges: &Option<Vec<KeyRange>>) -> (Vec<String>, Vec<String>) {
+    let mut conditions = Vec::new();
+    let mut values = Vec::new();
+
+    if let Some(key_ranges) = key_ranges {
+        for range in key_ranges {
+            let key = format!(
+                "json_extract('[\"' || replace(rtrim(keys, '/'), '/', '\",\"') || '\"]', '$[{}]')",
+                range.position
+            );
+            let mut condition = format!("{key} IS NOT NULL");
+
+            match (&range.min, &range.max) {
+                (Some(min), Some(max)) => {
+                    condition.push_str(&format!(" AND printf('%66s', {key}) BETWEEN ? AND ?"));
+                    values.extend([format!("{:>66}", min), format!("{:>66}", max)]);
+                }
+                (Some(min), None) => {
+                    condition.push_str(&format!(" AND printf('%66s', {key}) >= ?"));
+                    values.push(format!("{:>66}", min));
+                }
+                (None, Some(max)) => {
+                    condition.push_str(&format!(" AND printf('%66s', {key}) <= ?"));
+                    values.push(format!("{:>66}", max));
+                }
+                (None, None) => {}
+            }
+
+            conditions.push(format!("({})", condition));
+        }
+    }
+
+    (conditions, values)
+}
+
 fn keys_to_pattern(keys: &[String], use_regex: bool) -> String {
     let pattern = keys
         .iter()
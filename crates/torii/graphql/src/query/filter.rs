@@ -53,6 +53,23 @@ pub struct Filter {
     pub value: FilterValue,
 }
 
+// Compares a member of a model, to filter the entities which have this model
+#[derive(Debug)]
+pub struct MemberFilter {
+    pub model: String,
+    pub member: String,
+    pub comparator: Comparator,
+    pub value: String,
+}
+
+// Bounds the key of the entities at the given position, both bounds being inclusive hex strings
+#[derive(Debug)]
+pub struct KeyRange {
+    pub position: usize,
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
 pub fn parse_filter(input: &Name, value: FilterValue) -> Filter {
     for comparator in Comparator::iter() {
         if let Some(field) = input.strip_suffix(comparator.as_ref()) {
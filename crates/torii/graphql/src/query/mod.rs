@@ -1,23 +1,30 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use async_graphql::dynamic::TypeRef;
-use async_graphql::{Name, Value};
+use async_graphql::{Name, SelectionField, Value};
 use chrono::{DateTime, Utc};
 use convert_case::{Case, Casing};
 use dojo_types::primitive::{Primitive, SqlType};
 use regex::Regex;
 use sqlx::sqlite::SqliteRow;
-use sqlx::{Row, SqliteConnection};
+use sqlx::{Row, SqliteConnection, ValueRef};
+use tokio::sync::Mutex;
 use torii_core::sql::FELT_DELIMITER;
+use tracing::trace;
 
-use crate::constants::{BOOLEAN_TRUE, ENTITY_ID_COLUMN, INTERNAL_ENTITY_ID_KEY};
+use crate::constants::{
+    BOOLEAN_TRUE, ENTITY_ID_COLUMN, INTERNAL_ENTITY_ID_KEY, INTERNAL_RAW_KEYS_KEY, KEYS_COLUMN,
+};
 use crate::object::model_data::ModelMember;
-use crate::types::{TypeData, TypeMapping, ValueMapping};
+use crate::types::{FieldCasing, TypeData, TypeMapping, ValueMapping};
 
 pub mod data;
 pub mod filter;
 pub mod order;
 
+pub(crate) const LOG_TARGET: &str = "torii_graphql::query";
+
 pub async fn type_mapping_query(
     conn: &mut SqliteConnection,
     model_id: &str,
@@ -53,6 +60,140 @@ async fn fetch_model_members(
     .await
 }
 
+// Fetches the type mappings of several models at once, keyed by model id
+pub async fn type_mapping_query_many(
+    conn: &mut SqliteConnection,
+    model_ids: &[String],
+) -> sqlx::Result<HashMap<String, TypeMapping>> {
+    if model_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    trace!(target: LOG_TARGET, ?model_ids, "Fetching model members.");
+
+    let placeholders = vec!["?"; model_ids.len()].join(", ");
+    let query = format!(
+        r#"
+        SELECT
+            id,
+            model_id,
+            model_idx,
+            name,
+            type AS ty,
+            type_enum,
+            key,
+            executed_at,
+            created_at
+        from model_members WHERE model_id IN ({})
+        "#,
+        placeholders
+    );
+
+    let mut query = sqlx::query_as(&query);
+    for model_id in model_ids {
+        query = query.bind(model_id);
+    }
+    let model_members: Vec<ModelMember> = query.fetch_all(conn).await?;
+
+    model_ids
+        .iter()
+        .map(|model_id| {
+            let (root_members, nested_members): (Vec<&ModelMember>, Vec<&ModelMember>) =
+                model_members
+                    .iter()
+                    .filter(|member| &member.model_id == model_id)
+                    .partition(|member| member.model_idx == 0);
+
+            Ok((model_id.clone(), build_type_mapping(&root_members, &nested_members)?))
+        })
+        .collect()
+}
+
+// Type mappings already fetched while resolving a request, so that entities sharing models only
+// fetch them once. It is added to the data of each request, and must not outlive it as models can
+// be upgraded.
+#[derive(Debug, Default)]
+pub struct TypeMappingCache(Mutex<HashMap<String, TypeMapping>>);
+
+impl TypeMappingCache {
+    pub async fn type_mappings(
+        &self,
+        conn: &mut SqliteConnection,
+        model_ids: &[String],
+    ) -> sqlx::Result<HashMap<String, TypeMapping>> {
+        // the lock is held while fetching, so that entities resolved concurrently don't fetch the
+        // same models
+        let mut cache = self.0.lock().await;
+
+        let missing_ids: Vec<String> =
+            model_ids.iter().filter(|id| !cache.contains_key(*id)).cloned().collect();
+        cache.extend(type_mapping_query_many(conn, &missing_ids).await?);
+
+        Ok(model_ids
+            .iter()
+            .filter_map(|id| cache.get(id).map(|type_mapping| (id.clone(), type_mapping.clone())))
+            .collect())
+    }
+}
+
+// Keeps the fields of a type mapping which are in one of the selection sets, along with the
+// selected fields of their nested types. The selected fields are named with the casing of the
+// schema, the lists being kept for their compressed field as well
+pub fn project_type_mapping(
+    type_mapping: &TypeMapping,
+    selections: &[SelectionField<'_>],
+    field_casing: FieldCasing,
+) -> TypeMapping {
+    type_mapping
+        .iter()
+        .filter_map(|(field_name, type_data)| {
+            let name = field_casing.apply(field_name);
+            let compressed_name = field_casing.apply(&format!("{}_compressed", field_name));
+            let fields = selections
+                .iter()
+                .flat_map(|selection| selection.selection_set())
+                .filter(|field| field.name() == name || field.name() == compressed_name)
+                .collect::<Vec<_>>();
+
+            if fields.is_empty() {
+                return None;
+            }
+
+            Some((field_name.clone(), project_type_data(type_data, &fields, field_casing)))
+        })
+        .collect()
+}
+
+fn project_type_data(
+    type_data: &TypeData,
+    selections: &[SelectionField<'_>],
+    field_casing: FieldCasing,
+) -> TypeData {
+    match type_data {
+        TypeData::Simple(_) => type_data.clone(),
+        TypeData::Nested((type_ref, nested_mapping)) => TypeData::Nested((
+            type_ref.clone(),
+            project_type_mapping(nested_mapping, selections, field_casing),
+        )),
+        TypeData::List(inner) => {
+            TypeData::List(Box::new(project_type_data(inner, selections, field_casing)))
+        }
+        TypeData::Option(inner) => {
+            TypeData::Option(Box::new(project_type_data(inner, selections, field_casing)))
+        }
+        TypeData::Enum((type_ref, nested_mapping)) => {
+            // the active variant is always fetched, as the selected variants are resolved from it
+            let mut projected_mapping =
+                project_type_mapping(nested_mapping, selections, field_casing);
+            if let Some(option) = nested_mapping.get(&Name::new("option")) {
+                projected_mapping.insert(Name::new("option"), option.clone());
+            }
+
+            TypeData::Enum((type_ref.clone(), projected_mapping))
+        }
+    }
+}
+
 fn build_type_mapping(
     root_members: &[&ModelMember],
     nested_members: &[&ModelMember],
@@ -65,7 +206,7 @@ fn build_type_mapping(
         })
         .collect::<sqlx::Result<TypeMapping>>()?;
 
-    println!("{:#?}", type_mapping);
+    trace!(target: LOG_TARGET, ?type_mapping, "Built type mapping.");
 
     Ok(type_mapping)
 }
@@ -100,10 +241,54 @@ fn member_to_type_data(member: &ModelMember, nested_members: &[&ModelMember]) ->
         {
             TypeData::Simple(TypeRef::named("Enum"))
         }
+        "Enum" if member.ty.starts_with("Option<") => parse_option_type(member, nested_members),
+        "Enum" => parse_enum_type(member, nested_members),
         _ => parse_nested_type(member, nested_members),
     }
 }
 
+// Enums holding data are typed as objects, with their active variant in `option` and a field per
+// variant
+fn parse_enum_type(member: &ModelMember, nested_members: &[&ModelMember]) -> TypeData {
+    match parse_nested_type(member, nested_members) {
+        TypeData::Nested(nested) => TypeData::Enum(nested),
+        type_data => type_data,
+    }
+}
+
+// Options are stored like the other enums, but are typed as the value of their `Some` variant
+fn parse_option_type(member: &ModelMember, nested_members: &[&ModelMember]) -> TypeData {
+    match parse_nested_type(member, nested_members) {
+        TypeData::Nested((type_ref, nested_mapping)) => {
+            match nested_mapping.get(&Name::new("Some")) {
+                Some(inner) => TypeData::Option(Box::new(inner.clone())),
+                None => TypeData::Enum((type_ref, nested_mapping)),
+            }
+        }
+        type_data => type_data,
+    }
+}
+
+// The mapping of the table storing an option, holding its variant along with the value of `Some`
+pub fn option_type_mapping(inner: &TypeData) -> TypeMapping {
+    TypeMapping::from([
+        (Name::new("option"), TypeData::Simple(TypeRef::named("Enum"))),
+        (Name::new("Some"), inner.clone()),
+    ])
+}
+
+// An option resolves to the value of its `Some` variant, and to null for `None`
+pub fn option_value(value: Value) -> Value {
+    match value {
+        Value::Object(mut value_mapping)
+            if value_mapping.get(&Name::new("option")) == Some(&Value::from("Some")) =>
+        {
+            value_mapping.shift_remove(&Name::new("Some")).unwrap_or(Value::Null)
+        }
+        _ => Value::Null,
+    }
+}
+
 fn parse_nested_type(member: &ModelMember, nested_members: &[&ModelMember]) -> TypeData {
     let nested_mapping: TypeMapping = nested_members
         .iter()
@@ -147,6 +332,18 @@ fn parse_nested_type(member: &ModelMember, nested_members: &[&ModelMember]) -> T
     TypeData::Nested((TypeRef::named(namespaced), nested_mapping))
 }
 
+/// Checks that an identifier (table or column name) only contains characters allowed in
+/// the generated model tables, as identifiers can't be bound as query parameters.
+pub fn validate_identifier(identifier: &str) -> sqlx::Result<()> {
+    if identifier.is_empty()
+        || !identifier.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    {
+        return Err(sqlx::Error::Protocol(format!("Invalid identifier: {}", identifier)));
+    }
+
+    Ok(())
+}
+
 fn remove_hex_leading_zeros(value: Value) -> Value {
     if let Value::String(str_val) = &value {
         if !str_val.starts_with("0x") {
@@ -178,9 +375,7 @@ pub fn value_mapping_from_row(
 
             // handles felt arrays stored as string (ex: keys)
             if let (TypeRef::List(_), Value::String(s)) = (&type_data.type_ref(), &value) {
-                let mut felts: Vec<_> = s.split(FELT_DELIMITER).map(Value::from).collect();
-                felts.pop(); // removes empty item
-                value = Value::List(felts);
+                value = Value::List(split_felts(s).into_iter().map(Value::from).collect());
             }
 
             Ok((Name::new(field_name), value))
@@ -192,9 +387,24 @@ pub fn value_mapping_from_row(
         value_mapping.insert(Name::new(INTERNAL_ENTITY_ID_KEY), Value::from(entity_id));
     }
 
+    // the unsplit keys are kept for the clients needing them as stored
+    if let Ok(keys) = row.try_get::<String, &str>(KEYS_COLUMN) {
+        value_mapping.insert(Name::new(INTERNAL_RAW_KEYS_KEY), Value::from(keys));
+    }
+
     Ok(value_mapping)
 }
 
+// Splits felts stored as a string, each felt being followed by the delimiter (`0x1/0x2/`). No
+// felts give an empty list, and the order of the felts is preserved, along with the empty felts of
+// a malformed string so they are not silently dropped.
+pub fn split_felts(s: &str) -> Vec<&str> {
+    match s.strip_suffix(FELT_DELIMITER).unwrap_or(s) {
+        "" => vec![],
+        felts => felts.split(FELT_DELIMITER).collect(),
+    }
+}
+
 fn fetch_value(
     row: &SqliteRow,
     field_name: &str,
@@ -207,6 +417,11 @@ fn fetch_value(
         field_name.to_string().to_case(Case::Snake)
     };
 
+    // the columns of the enum variants which are not set are left empty
+    if row.try_get_raw(column_name.as_str())?.is_null() {
+        return Ok(Value::Null);
+    }
+
     match Primitive::from_str(type_name) {
         // fetch boolean
         Ok(Primitive::Bool(_)) => {
@@ -223,10 +438,7 @@ fn fetch_value(
         _ => {
             let value = match type_name {
                 "DateTime" => {
-                    let dt = row
-                        .try_get::<DateTime<Utc>, &str>(&column_name)
-                        .expect("Should be a stored as UTC Datetime")
-                        .to_rfc3339();
+                    let dt = row.try_get::<DateTime<Utc>, &str>(&column_name)?.to_rfc3339();
                     Value::from(dt)
                 }
                 _ => {
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_graphql::dynamic::{Object, Scalar, Schema, Subscription, Union};
 use convert_case::{Case, Casing};
 use sqlx::SqlitePool;
@@ -6,15 +6,20 @@ use torii_core::types::Model;
 
 use super::object::connection::page_info::PageInfoObject;
 use super::object::entity::EntityObject;
+use super::object::entity_aggregate::EntityAggregateObject;
+use super::object::entity_history::EntityHistoryObject;
 use super::object::event::EventObject;
-use super::object::model_data::ModelDataObject;
-use super::types::ScalarType;
+use super::object::model_data::{ComputedFields, ModelDataObject};
+use super::types::{
+    DatetimeFormat, EntityNames, FieldCasing, ListBatchSize, ListCompression, RowCap, ScalarType,
+};
 use crate::constants::{QUERY_TYPE_NAME, SUBSCRIPTION_TYPE_NAME};
+use crate::extension::{RequestSpans, Warnings};
 use crate::object::event_message::EventMessageObject;
 use crate::object::metadata::content::ContentObject;
 use crate::object::metadata::social::SocialObject;
 use crate::object::metadata::MetadataObject;
-use crate::object::model::ModelObject;
+use crate::object::model::{ModelFieldObject, ModelObject};
 use crate::object::transaction::TransactionObject;
 use crate::object::ObjectVariant;
 use crate::query::type_mapping_query;
@@ -24,8 +29,37 @@ use crate::query::type_mapping_query;
 // events, their schema is known but we generate them dynamically as well because async-graphql
 // does not allow mixing of static and dynamic schemas.
 pub async fn build_schema(pool: &SqlitePool) -> Result<Schema> {
+    build_schema_with_options(
+        pool,
+        DatetimeFormat::default(),
+        RowCap::default(),
+        EntityNames::default(),
+        ComputedFields::default(),
+        FieldCasing::default(),
+        ListCompression::default(),
+        ListBatchSize::default(),
+    )
+    .await
+}
+
+// Same as `build_schema`, with the format used for the datetimes, the maximum number of rows
+// fetched by the connections and the size of the batches the rows of the lists are fetched by
+// exposed in the schema context, the names of the entity queries, the fields computed from the
+// data of the models, the casing of the names of the model fields, and the compression of the lists
+#[allow(clippy::too_many_arguments)]
+pub async fn build_schema_with_options(
+    pool: &SqlitePool,
+    datetime_format: DatetimeFormat,
+    row_cap: RowCap,
+    entity_names: EntityNames,
+    computed_fields: ComputedFields,
+    field_casing: FieldCasing,
+    list_compression: ListCompression,
+    list_batch_size: ListBatchSize,
+) -> Result<Schema> {
     // build world gql objects
-    let (objects, unions) = build_objects(pool).await?;
+    let (objects, unions) =
+        build_objects(pool, entity_names, &computed_fields, field_casing, list_compression).await?;
 
     let mut schema_builder = Schema::build(QUERY_TYPE_NAME, None, Some(SUBSCRIPTION_TYPE_NAME));
     let mut query_root = Object::new(QUERY_TYPE_NAME);
@@ -96,17 +130,30 @@ pub async fn build_schema(pool: &SqlitePool) -> Result<Schema> {
         .register(query_root)
         .register(subscription_root)
         .data(pool.clone())
+        .data(datetime_format)
+        .data(row_cap)
+        .data(computed_fields)
+        .data(field_casing)
+        .data(list_batch_size)
+        .extension(Warnings)
+        .extension(RequestSpans)
         .finish()
         .map_err(|e| e.into())
 }
 
-async fn build_objects(pool: &SqlitePool) -> Result<(Vec<ObjectVariant>, Vec<Union>)> {
+async fn build_objects(
+    pool: &SqlitePool,
+    entity_names: EntityNames,
+    computed_fields: &ComputedFields,
+    field_casing: FieldCasing,
+    list_compression: ListCompression,
+) -> Result<(Vec<ObjectVariant>, Vec<Union>)> {
     let mut conn = pool.acquire().await?;
     let models: Vec<Model> = sqlx::query_as("SELECT * FROM models").fetch_all(&mut *conn).await?;
 
     // predefined objects
     let mut objects: Vec<ObjectVariant> = vec![
-        ObjectVariant::Resolvable(Box::new(EntityObject)),
+        ObjectVariant::Resolvable(Box::new(EntityObject::new(entity_names))),
         ObjectVariant::Resolvable(Box::new(EventMessageObject)),
         ObjectVariant::Resolvable(Box::new(EventObject)),
         ObjectVariant::Resolvable(Box::new(MetadataObject)),
@@ -115,6 +162,9 @@ async fn build_objects(pool: &SqlitePool) -> Result<(Vec<ObjectVariant>, Vec<Uni
         ObjectVariant::Basic(Box::new(SocialObject)),
         ObjectVariant::Basic(Box::new(ContentObject)),
         ObjectVariant::Basic(Box::new(PageInfoObject)),
+        ObjectVariant::Basic(Box::new(ModelFieldObject)),
+        ObjectVariant::Basic(Box::new(EntityAggregateObject)),
+        ObjectVariant::Basic(Box::new(EntityHistoryObject)),
     ];
 
     // model union object
@@ -132,10 +182,25 @@ async fn build_objects(pool: &SqlitePool) -> Result<(Vec<ObjectVariant>, Vec<Uni
 
             model_union = model_union.possible_type(&type_name);
 
+            // computed fields can't shadow the fields read from the database
+            let model_computed_fields = computed_fields.fields(&type_name).to_vec();
+            for computed_field in &model_computed_fields {
+                if type_mapping.keys().any(|name| field_casing.apply(name) == computed_field.name) {
+                    return Err(anyhow!(
+                        "The computed field '{}' collides with a field of the model '{}'.",
+                        computed_field.name,
+                        type_name
+                    ));
+                }
+            }
+
             objects.push(ObjectVariant::Resolvable(Box::new(ModelDataObject::new(
                 field_name,
                 type_name,
                 type_mapping.clone(),
+                model_computed_fields,
+                field_casing,
+                list_compression,
             ))));
 
             // add enum unions
@@ -11,18 +11,41 @@ use tokio::sync::broadcast::Receiver;
 use url::Url;
 use warp::{Filter, Rejection, Reply};
 
-use super::schema::build_schema;
+use super::object::model_data::ComputedFields;
+use super::schema::build_schema_with_options;
 use crate::constants::MODEL_TABLE;
 use crate::query::data::count_rows;
+use crate::query::TypeMappingCache;
+use crate::types::{
+    DatetimeFormat, EntityNames, FieldCasing, ListBatchSize, ListCompression, RowCap,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub async fn new(
     mut shutdown_rx: Receiver<()>,
     pool: &Pool<Sqlite>,
     external_url: Option<Url>,
+    datetime_format: DatetimeFormat,
+    row_cap: RowCap,
+    entity_names: EntityNames,
+    field_casing: FieldCasing,
+    list_compression: ListCompression,
+    list_batch_size: ListBatchSize,
 ) -> (SocketAddr, impl Future<Output = ()> + 'static) {
-    let schema = build_schema(pool).await.unwrap();
+    let schema = build_schema_with_options(
+        pool,
+        datetime_format,
+        row_cap,
+        entity_names,
+        ComputedFields::default(),
+        field_casing,
+        list_compression,
+        list_batch_size,
+    )
+    .await
+    .unwrap();
     let mut conn = pool.acquire().await.unwrap();
-    let num_models = count_rows(&mut conn, MODEL_TABLE, &None, &None).await.unwrap();
+    let num_models = count_rows(&mut conn, MODEL_TABLE, &None, &None, &None, &None).await.unwrap();
 
     let routes = graphql_filter(schema, external_url, num_models == 0);
     warp::serve(routes).bind_with_graceful_shutdown(([127, 0, 0, 1], 0), async move {
@@ -41,8 +64,8 @@ fn graphql_filter(
                 return Ok::<_, Rejection>(empty_response());
             }
 
-            // Execute query
-            let response = schema.execute(request).await;
+            // Execute query, type mappings are cached for the lifetime of the request
+            let response = schema.execute(request.data(TypeMappingCache::default())).await;
             // Return result
             Ok::<_, Rejection>(warp::reply::json(&response))
         },
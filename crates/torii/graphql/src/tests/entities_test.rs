@@ -1,13 +1,46 @@
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
     use anyhow::Result;
-    use async_graphql::dynamic::Schema;
+    use async_graphql::dynamic::{Schema, TypeRef};
+    use async_graphql::{ErrorExtensions, Name, Request};
+    use base64::engine::general_purpose;
+    use base64::Engine as _;
+    use chrono::{TimeZone, Utc};
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
+    use flate2::read::GzDecoder;
     use serde_json::Value;
+    use sqlx::SqlitePool;
+    use starknet::core::utils::get_selector_from_name;
     use starknet_crypto::{poseidon_hash_many, FieldElement};
+    use torii_core::sql::Sql;
+    use torii_core::types::Entity as EntityModel;
+    use tracing::subscriber::DefaultGuard;
+    use tracing::Level;
 
-    use crate::schema::build_schema;
+    use crate::constants::{
+        COMPRESSED_LIST_PREFIX, DEFAULT_LIST_BATCH_SIZE, INTERNAL_RAW_KEYS_KEY, MAX_NESTING_DEPTH,
+    };
+    use crate::error::{ExtractError, GraphqlObjectError};
+    use crate::object::connection::cursor;
+    use crate::object::entity::{
+        model_data_batched_query, model_data_recursive_query, EntityObject,
+    };
+    use crate::object::model_data::ComputedFields;
+    use crate::query::{type_mapping_query, validate_identifier, TypeMappingCache};
+    use crate::schema::{build_schema, build_schema_with_options};
     use crate::tests::{
-        run_graphql_query, spinup_types_test, Connection, Entity, Record, RecordSibling, Subrecord,
+        action_model, bag_model, event_id, inventory_model, player_model, register_test_model,
+        run_graphql_query, spinup_entities_test, spinup_model_test, spinup_types_test, Connection,
+        Entity, Record, RecordSibling, Subrecord,
+    };
+    use crate::types::{
+        DatetimeFormat, EntityNames, FieldCasing, GraphqlType, ListBatchSize, ListCompression,
+        RowCap, TypeData, TypeMapping,
     };
 
     async fn entities_query(schema: &Schema, arg: &str) -> Value {
@@ -85,6 +118,24 @@ mod tests {
         result.get("entity").ok_or("entity not found").unwrap().clone()
     }
 
+    // The data of the `model` of the entity keyed by `key`, fetched through its type mapping
+    async fn model_data(pool: &SqlitePool, model: &str, key: FieldElement) -> async_graphql::Value {
+        let mut conn = pool.acquire().await.unwrap();
+        let model_id = format!("{:#x}", get_selector_from_name(model).unwrap());
+        let type_mapping = type_mapping_query(&mut conn, &model_id).await.unwrap();
+        let entity_id = format!("{:#x}", poseidon_hash_many(&[key]));
+
+        model_data_recursive_query(
+            &mut conn,
+            vec![model.to_string()],
+            &entity_id,
+            &[],
+            &type_mapping,
+        )
+        .await
+        .unwrap()
+    }
+
     // End to end test spins up a test sequencer and deploys types-test project, this takes a while
     // to run so combine all related tests into one
     #[tokio::test(flavor = "multi_thread")]
@@ -247,4 +298,1870 @@ mod tests {
         assert_eq!(subrecord.subrecord_id, 1);
         Ok(())
     }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_entity_id_injection(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+
+        let mut conn = pool.acquire().await.unwrap();
+        let model_id = format!("{:#x}", get_selector_from_name("Record").unwrap());
+        let type_mapping = type_mapping_query(&mut conn, &model_id).await.unwrap();
+
+        let entity_id = format!("{:#x}", poseidon_hash_many(&[FieldElement::ONE]));
+        let data = model_data_recursive_query(
+            &mut conn,
+            vec!["Record".to_string()],
+            &entity_id,
+            &[],
+            &type_mapping,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(data, async_graphql::Value::Object(_)));
+
+        // a crafted id must be bound as a value and not dump the whole table
+        let data = model_data_recursive_query(
+            &mut conn,
+            vec!["Record".to_string()],
+            "' OR '1'='1",
+            &[],
+            &type_mapping,
+        )
+        .await
+        .unwrap();
+        assert_eq!(data, async_graphql::Value::Null);
+
+        // table names are validated before the query is executed
+        let result = model_data_recursive_query(
+            &mut conn,
+            vec!["Record; DROP TABLE entities; --".to_string()],
+            &entity_id,
+            &[],
+            &type_mapping,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_malformed_type_mapping(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+
+        let mut conn = pool.acquire().await.unwrap();
+        let entity_id = format!("{:#x}", poseidon_hash_many(&[FieldElement::ONE]));
+
+        // a felt column can't be decoded as a datetime, which is reported instead of panicking
+        let type_mapping = TypeMapping::from([(
+            Name::new("type_felt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        )]);
+        let result = model_data_recursive_query(
+            &mut conn,
+            vec!["Record".to_string()],
+            &entity_id,
+            &[],
+            &type_mapping,
+        )
+        .await;
+        assert!(matches!(result, Err(GraphqlObjectError::Sql(_))));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_nesting_too_deep(pool: SqlitePool) {
+        // a struct nested one level deeper than the maximum depth
+        let mut ty = Ty::Struct(Struct {
+            name: format!("Level{}", MAX_NESTING_DEPTH + 1),
+            children: vec![Member {
+                name: "value".to_string(),
+                key: false,
+                ty: Ty::Primitive(Primitive::U32(None)),
+            }],
+        });
+        for level in (1..=MAX_NESTING_DEPTH).rev() {
+            ty = Ty::Struct(Struct {
+                name: format!("Level{}", level),
+                children: vec![Member { name: "inner".to_string(), key: false, ty }],
+            });
+        }
+        let deep = Ty::Struct(Struct {
+            name: "Deep".to_string(),
+            children: vec![
+                Member {
+                    name: "player".to_string(),
+                    key: true,
+                    ty: Ty::Primitive(Primitive::ContractAddress(None)),
+                },
+                Member { name: "inner".to_string(), key: false, ty },
+            ],
+        });
+        spinup_model_test(&pool, deep, vec![]).await;
+
+        let mut conn = pool.acquire().await.unwrap();
+        let model_id = format!("{:#x}", get_selector_from_name("Deep").unwrap());
+        let type_mapping = type_mapping_query(&mut conn, &model_id).await.unwrap();
+        let entity_id = format!("{:#x}", poseidon_hash_many(&[FieldElement::ONE]));
+
+        let result = model_data_recursive_query(
+            &mut conn,
+            vec!["Deep".to_string()],
+            &entity_id,
+            &[],
+            &type_mapping,
+        )
+        .await;
+        let error = result.unwrap_err();
+        assert!(matches!(error, GraphqlObjectError::NestingTooDeep(_, MAX_NESTING_DEPTH)));
+        assert_eq!(error.code(), "NESTING_TOO_DEEP");
+
+        // the maximum depth is configurable
+        let result = model_data_batched_query(
+            &mut conn,
+            vec!["Deep".to_string()],
+            &entity_id,
+            &[],
+            &type_mapping,
+            DEFAULT_LIST_BATCH_SIZE,
+            MAX_NESTING_DEPTH + 1,
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_validate_identifier() {
+        assert!(validate_identifier("Record").is_ok());
+        assert!(validate_identifier("Record$type_nested_one").is_ok());
+        assert!(validate_identifier("").is_err());
+        assert!(validate_identifier("Record WHERE 1=1").is_err());
+        assert!(validate_identifier("Record'--").is_err());
+        assert!(validate_identifier("[Record]").is_err());
+    }
+
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // Captures the logs of the current thread at the given level, as long as the guard is held
+    fn capture_logs(level: Level) -> (LogBuffer, DefaultGuard) {
+        let buffer = LogBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        (buffer, tracing::subscriber::set_default(subscriber))
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_no_logs_at_default_level(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+
+        let (buffer, _guard) = capture_logs(Level::INFO);
+        model_data(&pool, "Record", FieldElement::ONE).await;
+
+        assert!(buffer.0.lock().unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_request_spans(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let (buffer, _guard) = capture_logs(Level::TRACE);
+
+        // the ids of the requests the queries of the model tables were made for
+        let request_ids = || {
+            let schema = schema.clone();
+            let buffer = buffer.clone();
+            async move {
+                let result = schema
+                    .execute("{ entities { edges { node { models { __typename } } } } }")
+                    .await;
+                assert!(result.errors.is_empty());
+
+                let logs =
+                    String::from_utf8(std::mem::take(&mut *buffer.0.lock().unwrap())).unwrap();
+                let lines = logs
+                    .lines()
+                    .filter(|line| line.contains("Fetching model table data."))
+                    .collect::<Vec<_>>();
+                assert!(!lines.is_empty());
+
+                lines
+                    .into_iter()
+                    .map(|line| {
+                        // along with the model and the entity of the data
+                        assert!(line.contains("model_data{model=Record entity_id="));
+
+                        let (_, request) = line.split_once("request{request_id=").unwrap();
+                        request.split(|c: char| !c.is_ascii_digit()).next().unwrap().to_string()
+                    })
+                    .collect::<HashSet<_>>()
+            }
+        };
+
+        // all the queries of a request share its id, which differs from one request to another
+        let first = request_ids().await;
+        let second = request_ids().await;
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+        assert_ne!(first, second);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_pagination_arguments(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let entities = entities_query(&schema, "(first: 1000)").await;
+        let connection: Connection<Entity> = serde_json::from_value(entities).unwrap();
+        assert_eq!(connection.edges.len(), 1);
+        assert!(!connection.page_info.has_next_page);
+
+        // over the maximum edge count, the connection is truncated
+        let res = schema.execute("{ entities(first: 1001) { totalCount } }").await;
+        assert!(res.errors.is_empty());
+        assert!(res.extensions.contains_key("warnings"));
+
+        // both directions at once
+        let res = schema.execute("{ entities(first: 1, last: 1) { totalCount } }").await;
+        assert_eq!(res.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_cursor_versions() {
+        let entity_id = format!("{:#x}", poseidon_hash_many(&[FieldElement::ONE]));
+
+        let encoded = cursor::encode(&entity_id, "2024-05-20 09:30:00");
+        assert_eq!(
+            cursor::decode(&encoded).unwrap(),
+            (entity_id.clone(), "2024-05-20 09:30:00".into())
+        );
+
+        // the value of the ordering field is kept whole
+        let encoded = cursor::encode(&entity_id, "a/b");
+        assert_eq!(cursor::decode(&encoded).unwrap(), (entity_id.clone(), "a/b".into()));
+
+        let encode_raw = |payload: &str| general_purpose::STANDARD.encode(payload);
+
+        let error = cursor::decode(&encode_raw(&format!("cursor/v0/{entity_id}/1"))).unwrap_err();
+        assert_eq!(
+            error.message,
+            format!(
+                "Cursor of version 0 is not supported, the current version is {}",
+                cursor::CURSOR_VERSION
+            )
+        );
+
+        let error = cursor::decode(&encode_raw(&format!("cursor/{entity_id}/1"))).unwrap_err();
+        assert!(error.message.starts_with("Unversioned cursor is not supported"));
+
+        assert!(cursor::decode(&encode_raw("cursor/v1/1")).is_err());
+        assert!(cursor::decode("not a cursor").is_err());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_incompatible_cursor(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let entities = entities_query(&schema, "(first: 1)").await;
+        let connection: Connection<Entity> = serde_json::from_value(entities).unwrap();
+        let entity_cursor = &connection.edges[0].cursor;
+
+        let res = schema
+            .execute(format!(
+                "{{ entities(first: 1, after: \"{entity_cursor}\") {{ totalCount }} }}"
+            ))
+            .await;
+        assert!(res.errors.is_empty());
+
+        let (entity_id, order_value) = cursor::decode(entity_cursor).unwrap();
+        let legacy_cursor =
+            general_purpose::STANDARD.encode(format!("cursor/{entity_id}/{order_value}"));
+        let res = schema
+            .execute(format!(
+                "{{ entities(first: 1, after: \"{legacy_cursor}\") {{ totalCount }} }}"
+            ))
+            .await;
+        assert_eq!(res.errors.len(), 1);
+        assert!(res.errors[0].message.contains("Unversioned cursor is not supported"));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_row_cap(pool: SqlitePool) {
+        let model = |player: u64| {
+            Ty::Struct(Struct {
+                name: "Position".to_string(),
+                children: vec![Member {
+                    name: "player".to_string(),
+                    key: true,
+                    ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::from(player)))),
+                }],
+            })
+        };
+
+        spinup_model_test(&pool, model(0), (0..5).map(model).collect()).await;
+        let schema = build_schema_with_options(
+            &pool,
+            DatetimeFormat::default(),
+            RowCap(3),
+            EntityNames::default(),
+            ComputedFields::default(),
+            FieldCasing::default(),
+            ListCompression::default(),
+            ListBatchSize::default(),
+        )
+        .await
+        .unwrap();
+
+        let entities = |arguments: &str| {
+            let query =
+                format!("{{ entities{} {{ totalCount edges {{ node {{ id }} }} }} }}", arguments);
+            let schema = schema.clone();
+            async move {
+                let response = schema.execute(query).await;
+                assert!(response.errors.is_empty());
+                let warnings = response.extensions.get("warnings").cloned();
+                let data = response.data.into_json().unwrap();
+                assert_eq!(data["entities"]["totalCount"], 5);
+                (data["entities"]["edges"].as_array().unwrap().len(), warnings)
+            }
+        };
+
+        // under the cap
+        assert_eq!(entities("(first: 2)").await, (2, None));
+        assert_eq!(entities("(last: 3)").await, (3, None));
+        assert_eq!(entities("(limit: 3, offset: 1)").await, (3, None));
+
+        // over the cap, the default limit included
+        let warning = Some(async_graphql::Value::List(vec![async_graphql::Value::from(
+            "The `entities` connection was truncated to 3 edges, the maximum allowed by the \
+             server.",
+        )]));
+        assert_eq!(entities("").await, (3, warning.clone()));
+        assert_eq!(entities("(first: 1001)").await, (3, warning.clone()));
+        assert_eq!(entities("(limit: 4, offset: 1)").await, (3, warning));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_ordered_by_member(pool: SqlitePool) {
+        let players = vec![player_model(1, 20), player_model(2, 10), player_model(3, 30)];
+        spinup_model_test(&pool, player_model(0, 0), players).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let scores = |arguments: &str| {
+            let query = format!(
+                "{{ entities{} {{ edges {{ node {{ models {{ ... on Player {{ score }} }} }} }} \
+                 }} }}",
+                arguments
+            );
+            let schema = schema.clone();
+            async move {
+                let result = run_graphql_query(&schema, &query).await;
+                result["entities"]["edges"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|edge| edge["node"]["models"][0]["score"].as_u64().unwrap())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // without ordering, the latest entities come first
+        assert_eq!(scores("").await, vec![30, 10, 20]);
+
+        assert_eq!(
+            scores(r#"(orderBy: { field: "Player.score", direction: ASC })"#).await,
+            vec![10, 20, 30]
+        );
+        assert_eq!(
+            scores(r#"(orderBy: { field: "Player.score", direction: DESC })"#).await,
+            vec![30, 20, 10]
+        );
+        assert_eq!(
+            scores(r#"(first: 2, orderBy: { field: "Player.score", direction: DESC })"#).await,
+            vec![30, 20]
+        );
+
+        // the model and member can't break out of the query
+        for field in ["Player.score; DROP TABLE entities", "Player'--.score", "Player"] {
+            let result = schema
+                .execute(format!(
+                    r#"{{ entities(orderBy: {{ field: "{}", direction: ASC }}) {{ totalCount }} }}"#,
+                    field
+                ))
+                .await;
+            assert_eq!(result.errors.len(), 1);
+        }
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_ordered_by_timestamp(pool: SqlitePool) {
+        let mut db = spinup_model_test(&pool, player_model(0, 0), vec![]).await;
+        for (id, executed_at) in [(1, 1710754508_u64), (2, 1710754488), (3, 1710754498)] {
+            db.set_entity(player_model(id, 0), &event_id(id), executed_at).await.unwrap();
+        }
+        sqlx::query("UPDATE entities SET created_at = executed_at").execute(&pool).await.unwrap();
+        let schema = build_schema(&pool).await.unwrap();
+
+        let timestamps = |field: &str, arguments: &str| {
+            let query = format!(
+                "{{ entities{} {{ edges {{ node {{ {}(epoch: true) }} }} }} }}",
+                arguments, field
+            );
+            let field = field.to_string();
+            let schema = schema.clone();
+            async move {
+                let result = run_graphql_query(&schema, &query).await;
+                result["entities"]["edges"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|edge| edge["node"][&field].as_i64().unwrap())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(
+            timestamps("executedAt", r#"(orderBy: { field: "executedAt", direction: ASC })"#).await,
+            vec![1710754488, 1710754498, 1710754508]
+        );
+        assert_eq!(
+            timestamps("createdAt", r#"(orderBy: { field: "createdAt", direction: DESC })"#).await,
+            vec![1710754508, 1710754498, 1710754488]
+        );
+        assert_eq!(
+            timestamps(
+                "executedAt",
+                r#"(first: 2, orderBy: { field: "executedAt", direction: DESC })"#
+            )
+            .await,
+            vec![1710754508, 1710754498]
+        );
+
+        // the datetimes are still formatted by default
+        let result =
+            run_graphql_query(&schema, "{ entities { edges { node { executedAt } } } }").await;
+        assert_eq!(
+            result["entities"]["edges"][0]["node"]["executedAt"],
+            "2024-03-18T09:34:58+00:00"
+        );
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_keys_range(pool: SqlitePool) {
+        let tile = |x: u32, y: u32| {
+            Ty::Struct(Struct {
+                name: "Tile".to_string(),
+                children: vec![
+                    Member {
+                        name: "x".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::U32(Some(x))),
+                    },
+                    Member {
+                        name: "y".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::U32(Some(y))),
+                    },
+                ],
+            })
+        };
+
+        let tiles =
+            [1, 2, 9, 10, 16, 255].iter().flat_map(|x| [tile(*x, 0), tile(*x, 1)]).collect();
+        spinup_model_test(&pool, tile(0, 0), tiles).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let count = |ranges: &str| {
+            let query = format!("{{ entities(keysRange: [{}]) {{ totalCount }} }}", ranges);
+            let schema = schema.clone();
+            async move {
+                let result = run_graphql_query(&schema, &query).await;
+                result["entities"]["totalCount"].as_u64().unwrap()
+            }
+        };
+
+        // the keys are compared as numbers, 0x10 being greater than 0x9
+        assert_eq!(count(r#"{ position: 0, min: "0x2", max: "0x10" }"#).await, 8);
+        assert_eq!(count(r#"{ position: 0, min: "0x0000a" }"#).await, 6);
+        assert_eq!(count(r#"{ position: 1, max: "0x0" }"#).await, 6);
+        let ranges =
+            r#"{ position: 0, min: "0x2", max: "0x10" }, { position: 1, min: "0x1", max: "0x1" }"#;
+        assert_eq!(count(ranges).await, 4);
+
+        // the positions are bounded by the keys of the models
+        let result = schema
+            .execute(r#"{ entities(keysRange: [{ position: 2, min: "0x1" }]) { totalCount } }"#)
+            .await;
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(
+            result.errors[0].message,
+            "The key position 2 is out of range, the models have at most 2 keys."
+        );
+
+        let result = schema
+            .execute(r#"{ entities(keysRange: [{ position: 0, min: "1' OR 1" }]) { totalCount } }"#)
+            .await;
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_filtered_total_count(pool: SqlitePool) {
+        let tile = |x: u32| {
+            Ty::Struct(Struct {
+                name: "Tile".to_string(),
+                children: vec![
+                    Member {
+                        name: "x".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::U32(Some(x))),
+                    },
+                    Member {
+                        name: "y".to_string(),
+                        key: false,
+                        ty: Ty::Primitive(Primitive::U32(Some(x % 2))),
+                    },
+                ],
+            })
+        };
+
+        spinup_model_test(&pool, tile(0), (0..8).map(tile).collect()).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        // the odd tiles from x = 2, ie 3, 5 and 7
+        let filters = r#"keysRange: [{ position: 0, min: "0x2" }],
+            where: [{ model: "Tile", member: "y", eq: "1" }]"#;
+        let unpaginated = entities_query(&schema, &format!("({})", filters)).await;
+        let edges = unpaginated["edges"].as_array().unwrap().len();
+        assert_eq!(edges, 3);
+        assert_eq!(unpaginated["totalCount"], edges);
+
+        // the count ignores the pagination but not the filters
+        for pagination in ["first: 2", "last: 1", "limit: 1, offset: 1", "limit: 2, offset: 2"] {
+            let result = entities_query(&schema, &format!("({}, {})", filters, pagination)).await;
+            assert_eq!(result["totalCount"], edges);
+        }
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_by_ids(pool: SqlitePool) {
+        let players = (1..=3).map(|id| player_model(id, 0)).collect();
+        spinup_model_test(&pool, player_model(0, 0), players).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let id = |player: u64| format!("{:#x}", poseidon_hash_many(&[FieldElement::from(player)]));
+        let ids = [id(3), id(1), id(42), id(3)];
+
+        let result = run_graphql_query(
+            &schema,
+            &format!(
+                "{{ entitiesByIds(ids: [{}]) {{ id }} }}",
+                ids.iter().map(|id| format!(r#""{}""#, id)).collect::<Vec<_>>().join(", ")
+            ),
+        )
+        .await;
+
+        // the entities are returned in the order of the ids, duplicates included, and the unknown
+        // ids are resolved to null
+        assert_eq!(
+            result["entitiesByIds"],
+            serde_json::json!([{ "id": id(3) }, { "id": id(1) }, null, { "id": id(3) }])
+        );
+
+        let result = run_graphql_query(&schema, "{ entitiesByIds(ids: []) { id } }").await;
+        assert_eq!(result["entitiesByIds"], serde_json::json!([]));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_aggregate(pool: SqlitePool) {
+        let players = vec![player_model(1, 20), player_model(2, 10), player_model(3, 30)];
+        spinup_model_test(&pool, player_model(0, 0), players).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let result = run_graphql_query(&schema, "{ entitiesAggregate { count max } }").await;
+        assert_eq!(result["entitiesAggregate"], serde_json::json!({ "count": 3, "max": null }));
+
+        let result = run_graphql_query(
+            &schema,
+            r#"{ entitiesAggregate(model: "Player", member: "score") { count min max sum avg } }"#,
+        )
+        .await;
+        assert_eq!(
+            result["entitiesAggregate"],
+            serde_json::json!({ "count": 3, "min": 10, "max": 30, "sum": 60, "avg": 20.0 })
+        );
+
+        // only the numeric members of a model are aggregated
+        for arguments in [
+            r#"model: "Player", member: "player""#,
+            r#"model: "Player", member: "unknown""#,
+            r#"model: "Player", member: "score; DROP TABLE entities""#,
+            r#"member: "score""#,
+        ] {
+            let result =
+                schema.execute(format!("{{ entitiesAggregate({}) {{ count }} }}", arguments)).await;
+            assert_eq!(result.errors.len(), 1);
+        }
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_custom_names(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+        let names =
+            EntityNames::new(Some("worldEntity".to_string()), Some("worldEntities".to_string()))
+                .unwrap();
+        let schema = build_schema_with_options(
+            &pool,
+            DatetimeFormat::default(),
+            RowCap::default(),
+            names,
+            ComputedFields::default(),
+            FieldCasing::default(),
+            ListCompression::default(),
+            ListBatchSize::default(),
+        )
+        .await
+        .unwrap();
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+
+        let result = run_graphql_query(
+            &schema,
+            &format!(
+                r#"{{
+                  worldEntity(id: "{:#x}") {{ id }}
+                  worldEntities {{ totalCount }}
+                  worldEntitiesByIds(ids: ["{:#x}"]) {{ id }}
+                }}"#,
+                id, id
+            ),
+        )
+        .await;
+        assert_eq!(result["worldEntity"]["id"], format!("{:#x}", id));
+        assert_eq!(result["worldEntities"]["totalCount"], 1);
+        assert_eq!(result["worldEntitiesByIds"].as_array().unwrap().len(), 1);
+
+        // the default names are not registered anymore
+        let result = schema.execute("{ entities { totalCount } }").await;
+        assert_eq!(result.errors.len(), 1);
+
+        // a single name falls back to the default one for the other query
+        let names = EntityNames::new(None, Some("allEntities".to_string())).unwrap();
+        assert_eq!((names.one(), names.many()), ("entity", "allEntities"));
+
+        for (one, many) in [("1entity", "entities"), ("entity", "all-entities"), ("__entity", "")] {
+            assert!(EntityNames::new(Some(one.to_string()), Some(many.to_string())).is_err());
+        }
+        assert!(EntityNames::new(Some("entities".to_string()), None).is_err());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_models_filtered_by_names(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+        let schema = build_schema(&pool).await.unwrap();
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+
+        let models_query = |names: &str| {
+            format!(r#"{{ entity(id: "{:#x}") {{ models{} {{ __typename }} }} }}"#, id, names)
+        };
+
+        let result = run_graphql_query(&schema, &models_query("")).await;
+        assert_eq!(result["entity"]["models"].as_array().unwrap().len(), 1);
+
+        let result = run_graphql_query(&schema, &models_query("(names: [])")).await;
+        assert!(result["entity"]["models"].as_array().unwrap().is_empty());
+
+        let result = run_graphql_query(&schema, &models_query("(names: [\"Unknown\"])")).await;
+        assert!(result["entity"]["models"].as_array().unwrap().is_empty());
+
+        let result =
+            run_graphql_query(&schema, &models_query("(names: [\"Record\", \"Unknown\"])")).await;
+        let models = result["entity"]["models"].as_array().unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0]["__typename"], "Record");
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_at_block_argument(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+        let schema = build_schema(&pool).await.unwrap();
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+        let message = "The at_block argument is not supported, torii does not store the history \
+                       of the entities.";
+
+        // entities are resolved from their latest state without the argument
+        let result = schema.execute("{ entities { totalCount } }").await;
+        assert!(result.errors.is_empty());
+        let result = schema.execute(format!(r#"{{ entity(id: "{:#x}") {{ id }} }}"#, id)).await;
+        assert!(result.errors.is_empty());
+
+        // and the history is never silently ignored
+        let result = schema.execute("{ entities(at_block: 10) { totalCount } }").await;
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].message, message);
+
+        let result = schema
+            .execute(format!(r#"{{ entity(id: "{:#x}", at_block: 10) {{ id }} }}"#, id))
+            .await;
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].message, message);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_models_missing_table(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        sqlx::query("DROP TABLE Record").execute(&pool).await.unwrap();
+
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+        let result = schema
+            .execute(format!(r#"{{ entity(id: "{:#x}") {{ models {{ __typename }} }} }}"#, id))
+            .await;
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(
+            result.errors[0].message,
+            "The data of the model 'Record' could not be found, the world may need to be \
+             re-indexed."
+        );
+
+        let code =
+            result.errors[0].extensions.as_ref().and_then(|extensions| extensions.get("code"));
+        assert_eq!(code, Some(&async_graphql::Value::from("TABLE_NOT_FOUND")));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_models_partial(pool: SqlitePool) {
+        let mut db = spinup_entities_test(&pool).await;
+
+        // a second model of the Record entity, keyed by `0x1` as well
+        let tag = Ty::Struct(Struct {
+            name: "Tag".to_string(),
+            children: vec![Member {
+                name: "player".to_string(),
+                key: true,
+                ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+            }],
+        });
+        register_test_model(&mut db, tag.clone()).await;
+        db.set_entity(tag, &event_id(1), 1710754478_u64).await.unwrap();
+        let schema = build_schema(&pool).await.unwrap();
+
+        sqlx::query("DROP TABLE Record").execute(&pool).await.unwrap();
+
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+        let models_query = |arguments: &str| {
+            format!(r#"{{ entity(id: "{:#x}") {{ models{} {{ __typename }} }} }}"#, id, arguments)
+        };
+
+        // the healthy models are returned, along with an error for each of the failing ones
+        let result = schema.execute(models_query("(partial: true)")).await;
+        assert_eq!(result.errors.len(), 1);
+        let code =
+            result.errors[0].extensions.as_ref().and_then(|extensions| extensions.get("code"));
+        assert_eq!(code, Some(&async_graphql::Value::from("TABLE_NOT_FOUND")));
+
+        let data = result.data.into_json().unwrap();
+        assert_eq!(data["entity"]["models"], serde_json::json!([{ "__typename": "Tag" }]));
+
+        // while the whole list still fails by default
+        let result = schema.execute(models_query("")).await;
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.data.into_json().unwrap()["entity"]["models"], Value::Null);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_raw_model(pool: SqlitePool) {
+        let position = |option: Option<u8>, x: Option<u32>, y: Option<u64>| {
+            Ty::Struct(Struct {
+                name: "Position".to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+                    },
+                    Member {
+                        name: "direction".to_string(),
+                        key: false,
+                        ty: Ty::Enum(Enum {
+                            name: "Direction".to_string(),
+                            option,
+                            options: vec![
+                                EnumOption { name: "Left".to_string(), ty: Ty::Tuple(vec![]) },
+                                EnumOption { name: "Right".to_string(), ty: Ty::Tuple(vec![]) },
+                            ],
+                        }),
+                    },
+                    Member {
+                        name: "x".to_string(),
+                        key: false,
+                        ty: Ty::Primitive(Primitive::U32(x)),
+                    },
+                    Member {
+                        name: "y".to_string(),
+                        key: false,
+                        ty: Ty::Primitive(Primitive::U64(y)),
+                    },
+                ],
+            })
+        };
+
+        let entities = vec![position(Some(1), Some(10), Some(20))];
+        spinup_model_test(&pool, position(None, None, None), entities).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+        let result = run_graphql_query(
+            &schema,
+            &format!(r#"{{ entity(id: "{:#x}") {{ rawModel(name: "Position") }} }}"#, id),
+        )
+        .await;
+
+        // the player, the option of the direction and the coordinates, one felt each
+        let raw = &result["entity"]["rawModel"];
+        assert_eq!(raw.as_array().unwrap().len(), 4);
+        assert_eq!(raw, &serde_json::json!(["0x1", "0x1", "0xa", "0x14"]));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_model_names(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        // the model data tables are never queried for the names
+        sqlx::query("DROP TABLE Record").execute(&pool).await.unwrap();
+
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+        let result = run_graphql_query(
+            &schema,
+            &format!(r#"{{ entity(id: "{:#x}") {{ modelNames }} }}"#, id),
+        )
+        .await;
+        assert_eq!(result["entity"]["modelNames"], serde_json::json!(["Record"]));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_keys_formats(pool: SqlitePool) {
+        let position = Ty::Struct(Struct {
+            name: "Position".to_string(),
+            children: vec![
+                Member {
+                    name: "player".to_string(),
+                    key: true,
+                    ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::from(255_u8)))),
+                },
+                Member {
+                    name: "x".to_string(),
+                    key: false,
+                    ty: Ty::Primitive(Primitive::U32(Some(10))),
+                },
+            ],
+        });
+        spinup_model_test(&pool, position.clone(), vec![position]).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let id = poseidon_hash_many(&[FieldElement::from(255_u8)]);
+        let result = run_graphql_query(
+            &schema,
+            &format!(
+                r#"{{ entity(id: "{:#x}") {{
+                    keys
+                    string: keys(as: STRING)
+                    hex: keys(as: HEX)
+                    decimal: keys(as: DECIMAL)
+                }} }}"#,
+                id
+            ),
+        )
+        .await;
+
+        // the keys are returned as stored by default
+        assert_eq!(result["entity"]["keys"], serde_json::json!(["0xff"]));
+        assert_eq!(result["entity"]["string"], serde_json::json!(["0xff"]));
+        assert_eq!(result["entity"]["hex"], serde_json::json!([format!("0x{:0>64}", "ff")]));
+        assert_eq!(result["entity"]["decimal"], serde_json::json!(["255"]));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_with_models(pool: SqlitePool) {
+        let model = |name: &str, player: Option<u8>| {
+            Ty::Struct(Struct {
+                name: name.to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::ContractAddress(
+                            player.map(FieldElement::from),
+                        )),
+                    },
+                    Member {
+                        name: "value".to_string(),
+                        key: false,
+                        ty: Ty::Primitive(Primitive::U32(player.map(u32::from))),
+                    },
+                ],
+            })
+        };
+
+        let mut db = spinup_model_test(&pool, model("Position", None), vec![]).await;
+        register_test_model(&mut db, model("Health", None)).await;
+
+        // the player 0x1 has both models, the player 0x2 only has a position
+        for (idx, (name, player)) in
+            [("Position", 1), ("Health", 1), ("Position", 2)].into_iter().enumerate()
+        {
+            db.set_entity(model(name, Some(player)), &event_id(idx as u64), 1710754478_u64)
+                .await
+                .unwrap();
+        }
+        let schema = build_schema(&pool).await.unwrap();
+
+        let entities_keys = |models: &'static str| {
+            let schema = schema.clone();
+            async move {
+                let query = format!("{{ entitiesWithModels(all: {}) {{ keys }} }}", models);
+                let result = run_graphql_query(&schema, &query).await;
+                result["entitiesWithModels"].clone()
+            }
+        };
+
+        // entities matching all the models, repeated ones being counted once
+        assert_eq!(
+            entities_keys(r#"["Position", "Health"]"#).await,
+            serde_json::json!([{ "keys": ["0x1"] }])
+        );
+        assert_eq!(
+            entities_keys(r#"["Health", "Position", "Health"]"#).await,
+            serde_json::json!([{ "keys": ["0x1"] }])
+        );
+
+        // entities matching some of the models, the most recently updated first
+        assert_eq!(
+            entities_keys(r#"["Position"]"#).await,
+            serde_json::json!([{ "keys": ["0x2"] }, { "keys": ["0x1"] }])
+        );
+
+        // entities matching none of the models
+        assert_eq!(entities_keys(r#"["Position", "Moves"]"#).await, serde_json::json!([]));
+
+        let result = schema.execute(r#"{ entitiesWithModels(all: ["Position'--"]) { id } }"#).await;
+        assert_eq!(result.errors[0].message, "'Position'--' is not a valid model name.");
+
+        let result = schema.execute("{ entitiesWithModels(all: []) { id } }").await;
+        assert_eq!(result.errors[0].message, "At least one model is required.");
+    }
+
+    #[test]
+    fn test_graphql_object_error_codes() {
+        let code = |error: GraphqlObjectError| {
+            error.extend().extensions.and_then(|extensions| extensions.get("code").cloned())
+        };
+
+        assert_eq!(
+            code(GraphqlObjectError::UnexpectedValue("Object")),
+            Some("UNEXPECTED_VALUE".into())
+        );
+        assert_eq!(
+            code(ExtractError::NotFound("id".to_string()).into()),
+            Some("MISSING_FIELD".into())
+        );
+        assert_eq!(
+            code(ExtractError::NotString("id".to_string()).into()),
+            Some("INVALID_FIELD".into())
+        );
+        assert_eq!(
+            code(GraphqlObjectError::MalformedData("Record".to_string())),
+            Some("MALFORMED_DATA".into())
+        );
+        assert_eq!(code(sqlx::Error::RowNotFound.into()), Some("DATABASE_ERROR".into()));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_where_members(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let count = |result: &Value| result["totalCount"].as_i64().unwrap();
+
+        // numeric comparisons, record_id is 0
+        let result = entities_query(
+            &schema,
+            r#"(where: [{ model: "Record", member: "record_id", eq: "0" }])"#,
+        )
+        .await;
+        assert_eq!(count(&result), 1);
+        assert_eq!(result["edges"].as_array().unwrap().len(), 1);
+
+        let result = entities_query(
+            &schema,
+            r#"(where: [{ model: "Record", member: "record_id", gt: "0" }])"#,
+        )
+        .await;
+        assert_eq!(count(&result), 0);
+        assert!(result["edges"].as_array().unwrap().is_empty());
+
+        let result = entities_query(
+            &schema,
+            r#"(where: [{ model: "Record", member: "record_id", gte: "0", lt: "10" }])"#,
+        )
+        .await;
+        assert_eq!(count(&result), 1);
+
+        // string comparisons, type_felt is 0x1 stored as a zero padded hex string
+        let result = entities_query(
+            &schema,
+            r#"(where: [{ model: "Record", member: "type_felt", eq: "0x1" }])"#,
+        )
+        .await;
+        assert_eq!(count(&result), 1);
+
+        let result = entities_query(
+            &schema,
+            r#"(where: [{ model: "Record", member: "type_felt", neq: "0x1" }])"#,
+        )
+        .await;
+        assert_eq!(count(&result), 0);
+
+        // conditions are combined with AND
+        let result = entities_query(
+            &schema,
+            r#"(where: [
+                { model: "Record", member: "type_felt", eq: "0x1" },
+                { model: "Record", member: "record_id", lte: "0" }
+            ])"#,
+        )
+        .await;
+        assert_eq!(count(&result), 1);
+
+        let result = entities_query(
+            &schema,
+            r#"(where: [
+                { model: "Record", member: "type_felt", eq: "0x1" },
+                { model: "Record", member: "record_id", neq: "0" }
+            ])"#,
+        )
+        .await;
+        assert_eq!(count(&result), 0);
+
+        // identifiers can't be used to inject sql
+        let query = r#"
+          {
+            entities(where: [{ model: "Record] WHERE 1=1; --", member: "record_id", eq: "0" }]) {
+              totalCount
+            }
+          }
+        "#;
+        let result = schema.execute(query).await;
+        assert!(!result.errors.is_empty());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entities_where_uppercase_hex(pool: SqlitePool) {
+        spinup_model_test(&pool, player_model(0, 0), vec![player_model(0xab, 1)]).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        // hex values are compared against the lowercase hex strings of the database
+        for player in ["0xab", "0xAB", "0x00Ab"] {
+            let arguments =
+                format!(r#"(where: [{{ model: "Player", member: "player", eq: "{}" }}])"#, player);
+            let result = entities_query(&schema, &arguments).await;
+            assert_eq!(result["totalCount"].as_i64().unwrap(), 1, "{}", player);
+        }
+    }
+
+    #[test]
+    fn test_entity_value_mapping_datetime_format() {
+        let datetime = Utc.with_ymd_and_hms(2024, 3, 18, 9, 34, 38).unwrap();
+        let entity = EntityModel {
+            id: "0x1".to_string(),
+            keys: "0x1/".to_string(),
+            event_id: "0x0:0x0:0x0".to_string(),
+            executed_at: datetime,
+            created_at: datetime,
+            updated_at: datetime,
+            model: "Record".to_string(),
+        };
+
+        let legacy = EntityObject::value_mapping(entity.clone(), DatetimeFormat::Legacy);
+        let rfc3339 = EntityObject::value_mapping(entity, DatetimeFormat::Rfc3339);
+
+        for field in ["createdAt", "updatedAt", "executedAt"] {
+            assert_eq!(legacy[&Name::new(field)], "2024-03-18T09:34:38Z".into());
+            assert_eq!(rfc3339[&Name::new(field)], "2024-03-18T09:34:38+00:00".into());
+        }
+    }
+
+    #[test]
+    fn test_entity_value_mapping_keys() {
+        let datetime = Utc.with_ymd_and_hms(2024, 3, 18, 9, 34, 38).unwrap();
+        let value_mapping = |keys: &str| {
+            EntityObject::value_mapping(
+                EntityModel {
+                    id: "0x1".to_string(),
+                    keys: keys.to_string(),
+                    event_id: "0x0:0x0:0x0".to_string(),
+                    executed_at: datetime,
+                    created_at: datetime,
+                    updated_at: datetime,
+                    model: "Record".to_string(),
+                },
+                DatetimeFormat::Legacy,
+            )
+        };
+
+        for (raw_keys, keys) in [
+            ("/", vec![]),
+            ("0x1/", vec!["0x1"]),
+            ("0x3/0x1/0x2/", vec!["0x3", "0x1", "0x2"]),
+            // empty keys of a malformed string are kept rather than silently dropped
+            ("0x1//0x2/", vec!["0x1", "", "0x2"]),
+        ] {
+            let mapping = value_mapping(raw_keys);
+            assert_eq!(mapping[&Name::new("keys")], async_graphql::Value::from(keys));
+            assert_eq!(mapping[&Name::new(INTERNAL_RAW_KEYS_KEY)], raw_keys.into());
+        }
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_raw_keys(pool: SqlitePool) {
+        spinup_entities_test(&pool).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+        let result = run_graphql_query(
+            &schema,
+            &format!(r#"{{ entity(id: "{:#x}") {{ keys rawKeys }} }}"#, id),
+        )
+        .await;
+        assert_eq!(result["entity"]["keys"], serde_json::json!(["0x1"]));
+        assert_eq!(result["entity"]["rawKeys"], "0x1/");
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_event_history(pool: SqlitePool) {
+        let position = |x: u32| {
+            Ty::Struct(Struct {
+                name: "Position".to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+                    },
+                    Member {
+                        name: "x".to_string(),
+                        key: false,
+                        ty: Ty::Primitive(Primitive::U32(Some(x))),
+                    },
+                ],
+            })
+        };
+
+        let mut db = spinup_model_test(&pool, position(0), vec![]).await;
+        // the last event is set again, which doesn't add it twice to the history
+        for (x, event) in [0, 1, 2, 2].into_iter().enumerate() {
+            db.set_entity(position(x as u32), &event_id(event), 1710754478_u64 + event)
+                .await
+                .unwrap();
+        }
+        let schema = build_schema(&pool).await.unwrap();
+
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+        let query = format!(
+            r#"
+          {{
+            entity(id: "{:#x}") {{
+              eventId
+              eventHistory {{
+                eventId
+                executedAt(epoch: true)
+              }}
+            }}
+          }}
+        "#,
+            id
+        );
+        let result = run_graphql_query(&schema, &query).await;
+        let history = result["entity"]["eventHistory"].as_array().unwrap();
+        assert_eq!(history.len(), 3);
+        for (event, entry) in history.iter().enumerate() {
+            assert_eq!(entry["eventId"], event_id(event as u64));
+            assert_eq!(entry["executedAt"], 1710754478_u64 + event as u64);
+        }
+        assert_eq!(history.last().unwrap()["eventId"], result["entity"]["eventId"]);
+
+        // the databases indexed without the history report it rather than an empty one
+        sqlx::query("DROP TABLE entity_history").execute(&pool).await.unwrap();
+        let result = schema.execute(query).await;
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(
+            result.errors[0].message,
+            "The event history of the entities is not indexed, the database may need to be \
+             migrated."
+        );
+        let code =
+            result.errors[0].extensions.as_ref().and_then(|extensions| extensions.get("code"));
+        assert_eq!(code, Some(&async_graphql::Value::from("TABLE_NOT_FOUND")));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_queries_per_table(pool: SqlitePool) {
+        let inventory = inventory_model(&[(1, 10), (2, 20), (3, 30)]);
+        spinup_model_test(&pool, inventory_model(&[(0, 0)]), vec![inventory]).await;
+
+        let (buffer, _guard) = capture_logs(Level::TRACE);
+        let data = model_data(&pool, "Inventory", FieldElement::ONE).await;
+
+        let async_graphql::Value::Object(inventory) = data else { panic!("expected an object") };
+        let async_graphql::Value::List(items) = &inventory[&Name::new("items")] else {
+            panic!("expected a list")
+        };
+        assert_eq!(items.len(), 3);
+
+        // Inventory, Inventory$items and Inventory$items$data are each fetched once, where fetching
+        // every array element separately used to take 1 + 1 + 3 queries
+        let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(logs.matches("Fetching model table data.").count(), 3);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_models_projection(pool: SqlitePool) {
+        let inventory = inventory_model(&[(1, 10), (2, 20), (3, 30)]);
+        spinup_model_test(&pool, inventory_model(&[(0, 0)]), vec![inventory]).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let (buffer, _guard) = capture_logs(Level::TRACE);
+
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+        let models = |selection: &str| {
+            let query = format!(
+                r#"{{ entity(id: "{:#x}") {{ models {{ ... on Inventory {{ {} }} }} }} }}"#,
+                id, selection
+            );
+            let schema = schema.clone();
+            let buffer = buffer.clone();
+            async move {
+                buffer.0.lock().unwrap().clear();
+                let result = run_graphql_query(&schema, &query).await;
+                let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+                let queries = logs.matches("Fetching model table data.").count();
+                (result["entity"]["models"][0].clone(), queries)
+            }
+        };
+
+        // the unrequested items are not fetched, only the Inventory table is queried
+        let (model, queries) = models("player").await;
+        assert_eq!(model, serde_json::json!({ "player": "0x1" }));
+        assert_eq!(queries, 1);
+
+        // Inventory, Inventory$items and Inventory$items$data are queried for the items
+        let (model, queries) = models("items { id }").await;
+        assert_eq!(model, serde_json::json!({ "items": [{ "id": 1 }, { "id": 2 }, { "id": 3 }] }));
+        assert_eq!(queries, 3);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_nested_arrays(pool: SqlitePool) {
+        let u32_array = |values: &[u32]| {
+            Ty::Array(values.iter().map(|v| Ty::Primitive(Primitive::U32(Some(*v)))).collect())
+        };
+        let grid = |cells: Vec<Ty>, values: Ty| {
+            Ty::Struct(Struct {
+                name: "Grid".to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+                    },
+                    Member { name: "cells".to_string(), key: false, ty: Ty::Array(cells) },
+                    Member { name: "values".to_string(), key: false, ty: values },
+                ],
+            })
+        };
+
+        let model =
+            grid(vec![Ty::Array(vec![Ty::Primitive(Primitive::U32(None))])], u32_array(&[0]));
+        let grids =
+            vec![grid(vec![u32_array(&[1, 2]), u32_array(&[3]), u32_array(&[])], u32_array(&[4]))];
+        spinup_model_test(&pool, model, grids).await;
+        let data = model_data(&pool, "Grid", FieldElement::ONE).await;
+
+        let list = |values: &[i64]| {
+            async_graphql::Value::List(
+                values.iter().map(|v| async_graphql::Value::from(*v)).collect(),
+            )
+        };
+        let async_graphql::Value::Object(model) = data else { panic!("expected an object") };
+        assert_eq!(
+            model[&Name::new("cells")],
+            async_graphql::Value::List(vec![list(&[1, 2]), list(&[3]), list(&[])])
+        );
+        assert_eq!(model[&Name::new("values")], list(&[4]));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_batched_list(pool: SqlitePool) {
+        let bag = bag_model(FieldElement::ONE, (0..2500).collect());
+        spinup_model_test(&pool, bag_model(FieldElement::ONE, vec![0]), vec![bag]).await;
+
+        let (buffer, _guard) = capture_logs(Level::TRACE);
+
+        let mut conn = pool.acquire().await.unwrap();
+        let model_id = format!("{:#x}", get_selector_from_name("Bag").unwrap());
+        let type_mapping = type_mapping_query(&mut conn, &model_id).await.unwrap();
+        let entity_id = format!("{:#x}", poseidon_hash_many(&[FieldElement::ONE]));
+        let data = model_data_batched_query(
+            &mut conn,
+            vec!["Bag".to_string()],
+            &entity_id,
+            &[],
+            &type_mapping,
+            1000,
+            MAX_NESTING_DEPTH,
+        )
+        .await
+        .unwrap();
+
+        // the list is rebuilt in order, across the batches
+        let async_graphql::Value::Object(bag) = data else { panic!("expected an object") };
+        assert_eq!(
+            bag[&Name::new("values")],
+            async_graphql::Value::List((0..2500).map(async_graphql::Value::from).collect())
+        );
+
+        // Bag$values is fetched by batches of 1000 rows
+        let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(logs.matches("Fetching model list batch.").count(), 3);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_list_batch_size(pool: SqlitePool) {
+        let bag = bag_model(FieldElement::ONE, (0..25).collect());
+        spinup_model_test(&pool, bag_model(FieldElement::ONE, vec![0]), vec![bag]).await;
+        let schema = build_schema_with_options(
+            &pool,
+            DatetimeFormat::default(),
+            RowCap::default(),
+            EntityNames::default(),
+            ComputedFields::default(),
+            FieldCasing::default(),
+            ListCompression::default(),
+            ListBatchSize(10),
+        )
+        .await
+        .unwrap();
+
+        let (buffer, _guard) = capture_logs(Level::TRACE);
+
+        let query = format!(
+            r#"{{ entity(id: "{:#x}") {{ models {{ ... on Bag {{ values }} }} }} }}"#,
+            poseidon_hash_many(&[FieldElement::ONE])
+        );
+        let result = run_graphql_query(&schema, &query).await;
+        assert_eq!(
+            result["entity"]["models"][0]["values"],
+            serde_json::Value::from((0..25).collect::<Vec<_>>())
+        );
+
+        // Bag$values is fetched by batches of the configured size
+        let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(logs.matches("Fetching model list batch.").count(), 3);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_list_index(pool: SqlitePool) {
+        let bag = bag_model(FieldElement::ONE, (10..13).collect());
+        spinup_model_test(&pool, bag_model(FieldElement::ONE, vec![0]), vec![bag]).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let id = poseidon_hash_many(&[FieldElement::ONE]);
+        let query = |selection: &str| {
+            format!(
+                r#"{{ entity(id: "{:#x}") {{ models {{ ... on Bag {{ {} }} }} }} }}"#,
+                id, selection
+            )
+        };
+
+        let result = run_graphql_query(&schema, &query("values")).await;
+        assert_eq!(result["entity"]["models"][0]["values"], serde_json::json!([10, 11, 12]));
+
+        // the element is returned alone, rather than in a list
+        let result = run_graphql_query(&schema, &query("values_at(index: 1)")).await;
+        assert_eq!(result["entity"]["models"][0]["values_at"], serde_json::json!(11));
+
+        let result = run_graphql_query(&schema, &query("values_at(index: 3)")).await;
+        assert_eq!(result["entity"]["models"][0]["values_at"], serde_json::Value::Null);
+
+        let result = schema.execute(query("values_at(index: -1)")).await;
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].message, "The index of a list element must be non-negative");
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_list_compression(pool: SqlitePool) {
+        // a small list for the player 0x1 and a large one for the player 0x2
+        let bags = vec![
+            bag_model(FieldElement::ONE, (10..13).collect()),
+            bag_model(FieldElement::TWO, (0..100).collect()),
+        ];
+        spinup_model_test(&pool, bag_model(FieldElement::ONE, vec![0]), bags).await;
+        let schema = build_schema_with_options(
+            &pool,
+            DatetimeFormat::default(),
+            RowCap::default(),
+            EntityNames::default(),
+            ComputedFields::default(),
+            FieldCasing::default(),
+            ListCompression(Some(64)),
+            ListBatchSize::default(),
+        )
+        .await
+        .unwrap();
+
+        let query = |player: FieldElement, selection: &str| {
+            format!(
+                r#"{{ entity(id: "{:#x}") {{ models {{ ... on Bag {{ {} }} }} }} }}"#,
+                poseidon_hash_many(&[player]),
+                selection
+            )
+        };
+
+        // the lists under the threshold are not compressed, and only the selected field is sent
+        let result =
+            run_graphql_query(&schema, &query(FieldElement::ONE, "values_compressed")).await;
+        assert_eq!(
+            result["entity"]["models"][0],
+            serde_json::json!({ "values_compressed": "[10,11,12]" })
+        );
+
+        // the larger ones are sent as their compressed JSON
+        let result =
+            run_graphql_query(&schema, &query(FieldElement::TWO, "values_compressed")).await;
+        let compressed = result["entity"]["models"][0]["values_compressed"].as_str().unwrap();
+        let encoded = compressed.strip_prefix(COMPRESSED_LIST_PREFIX).unwrap();
+        let gzipped = general_purpose::STANDARD.decode(encoded).unwrap();
+        let decompressed: Value =
+            serde_json::from_reader(GzDecoder::new(gzipped.as_slice())).unwrap();
+        assert_eq!(decompressed, serde_json::json!((0..100).collect::<Vec<_>>()));
+
+        // the lists themselves are left as is
+        let result = run_graphql_query(&schema, &query(FieldElement::TWO, "values")).await;
+        assert_eq!(
+            result["entity"]["models"][0]["values"],
+            serde_json::json!((0..100).collect::<Vec<_>>())
+        );
+
+        // and the compressed fields are only in the schemas compressing the lists
+        let schema = build_schema(&pool).await.unwrap();
+        let result = schema.execute(query(FieldElement::TWO, "values_compressed")).await;
+        assert!(!result.errors.is_empty());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_models_type_mappings_cached(pool: SqlitePool) {
+        let mut db = spinup_entities_test(&pool).await;
+
+        // two more Record entities, sharing the model of the fixture one
+        for key in [2u8, 3u8] {
+            db.set_entity(
+                Ty::Struct(Struct {
+                    name: "Record".to_string(),
+                    children: vec![
+                        Member {
+                            name: "depth".to_string(),
+                            key: false,
+                            ty: Ty::Enum(Enum {
+                                name: "Depth".to_string(),
+                                option: Some(0),
+                                options: vec![
+                                    EnumOption { name: "Zero".to_string(), ty: Ty::Tuple(vec![]) },
+                                    EnumOption { name: "One".to_string(), ty: Ty::Tuple(vec![]) },
+                                    EnumOption { name: "Two".to_string(), ty: Ty::Tuple(vec![]) },
+                                    EnumOption { name: "Three".to_string(), ty: Ty::Tuple(vec![]) },
+                                ],
+                            }),
+                        },
+                        Member {
+                            name: "typeContractAddress".to_string(),
+                            key: true,
+                            ty: Ty::Primitive(Primitive::ContractAddress(Some(
+                                FieldElement::from(key),
+                            ))),
+                        },
+                    ],
+                }),
+                &event_id(key.into()),
+                1710754478_u64,
+            )
+            .await
+            .unwrap();
+        }
+        let schema = build_schema(&pool).await.unwrap();
+
+        let (buffer, _guard) = capture_logs(Level::TRACE);
+
+        let request = Request::new("{ entities { edges { node { models { __typename } } } } }")
+            .data(TypeMappingCache::default());
+        let result = schema.execute(request).await;
+        assert!(result.errors.is_empty());
+
+        let result = result.data.into_json().unwrap();
+        let edges = result["entities"]["edges"].as_array().unwrap();
+        assert_eq!(edges.len(), 3);
+        for edge in edges {
+            assert_eq!(edge["node"]["models"][0]["__typename"], "Record");
+        }
+
+        // the model members are fetched once for the single distinct model, not once per entity
+        let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(logs.matches("Fetching model members.").count(), 1);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_enum_struct_payload(pool: SqlitePool) {
+        let action = action_model(Some(1), Some(3), Some(4));
+        spinup_model_test(&pool, action_model(None, None, None), vec![action]).await;
+        let data = model_data(&pool, "Action", FieldElement::ONE).await;
+
+        // the variant payload is rebuilt from all the members of its struct
+        let async_graphql::Value::Object(model) = data else { panic!("expected an object") };
+        let async_graphql::Value::Object(kind) = &model[&Name::new("kind")] else {
+            panic!("expected an object")
+        };
+        assert_eq!(kind[&Name::new("option")], async_graphql::Value::from("Move"));
+        let async_graphql::Value::Object(payload) = &kind[&Name::new("Move")] else {
+            panic!("expected an object")
+        };
+        assert_eq!(payload[&Name::new("x")], async_graphql::Value::from(3));
+        assert_eq!(payload[&Name::new("y")], async_graphql::Value::from(4));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_enum_active_variant(pool: SqlitePool) {
+        let mut db = spinup_model_test(&pool, action_model(None, None, None), vec![]).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let kinds = |selection: &'static str| {
+            let schema = schema.clone();
+            async move {
+                let query = format!(
+                    r#"{{
+                        entity(id: "{:#x}") {{
+                            models {{ ... on Action {{ kind {{ {selection} }} }} }}
+                        }}
+                        actionModels {{ edges {{ node {{ kind {{ {selection} }} }} }} }}
+                    }}"#,
+                    poseidon_hash_many(&[FieldElement::ONE])
+                );
+                let result = run_graphql_query(&schema, &query).await;
+                (
+                    result["entity"]["models"][0]["kind"].clone(),
+                    result["actionModels"]["edges"][0]["node"]["kind"].clone(),
+                )
+            }
+        };
+
+        // the active variant is returned along with its name
+        db.set_entity(action_model(Some(1), Some(3), Some(4)), &event_id(0), 1710754478_u64)
+            .await
+            .unwrap();
+        let moving = serde_json::json!({ "option": "Move", "Move": { "x": 3, "y": 4 } });
+        assert_eq!(kinds("option Move { x y }").await, (moving.clone(), moving));
+
+        // even when its name is not selected
+        let moving = serde_json::json!({ "Move": { "x": 3, "y": 4 } });
+        assert_eq!(kinds("Move { x y }").await, (moving.clone(), moving));
+
+        // and only this one, the table of the previous variant still holding its data
+        db.set_entity(action_model(Some(0), None, None), &event_id(1), 1710754478_u64)
+            .await
+            .unwrap();
+        let idle = serde_json::json!({ "option": "Idle", "Move": null });
+        assert_eq!(kinds("option Move { x y }").await, (idle.clone(), idle));
+
+        let idle = serde_json::json!({ "Move": null });
+        assert_eq!(kinds("Move { x y }").await, (idle.clone(), idle));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_struct_option_member(pool: SqlitePool) {
+        // a struct with a member named `option` is not an enum
+        let settings = |option: Option<u8>, level: Option<u8>| {
+            Ty::Struct(Struct {
+                name: "Settings".to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+                    },
+                    Member {
+                        name: "audio".to_string(),
+                        key: false,
+                        ty: Ty::Struct(Struct {
+                            name: "Audio".to_string(),
+                            children: vec![
+                                Member {
+                                    name: "option".to_string(),
+                                    key: false,
+                                    ty: Ty::Enum(Enum {
+                                        name: "Mode".to_string(),
+                                        option,
+                                        options: vec![
+                                            EnumOption {
+                                                name: "Mono".to_string(),
+                                                ty: Ty::Tuple(vec![]),
+                                            },
+                                            EnumOption {
+                                                name: "Stereo".to_string(),
+                                                ty: Ty::Tuple(vec![]),
+                                            },
+                                        ],
+                                    }),
+                                },
+                                Member {
+                                    name: "level".to_string(),
+                                    key: false,
+                                    ty: Ty::Primitive(Primitive::U8(level)),
+                                },
+                            ],
+                        }),
+                    },
+                ],
+            })
+        };
+
+        spinup_model_test(&pool, settings(None, None), vec![settings(Some(1), Some(7))]).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let result = run_graphql_query(
+            &schema,
+            "{ settingsModels { edges { node { audio { option level } } } } }",
+        )
+        .await;
+        assert_eq!(
+            result["settingsModels"]["edges"][0]["node"]["audio"],
+            serde_json::json!({ "option": "Stereo", "level": 7 })
+        );
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_data_option(pool: SqlitePool) {
+        let option = |option: Option<u8>, some: Ty| {
+            Ty::Enum(Enum {
+                name: "Option<T>".to_string(),
+                option,
+                options: vec![
+                    EnumOption { name: "Some".to_string(), ty: some },
+                    EnumOption { name: "None".to_string(), ty: Ty::Tuple(vec![]) },
+                ],
+            })
+        };
+        let loot = |player: FieldElement, bonus: Option<u32>, position: Option<(u32, u32)>| {
+            let (x, y) = position.map_or((None, None), |(x, y)| (Some(x), Some(y)));
+            Ty::Struct(Struct {
+                name: "Loot".to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::ContractAddress(Some(player))),
+                    },
+                    Member {
+                        name: "bonus".to_string(),
+                        key: false,
+                        ty: option(
+                            Some(if bonus.is_some() { 0 } else { 1 }),
+                            Ty::Primitive(Primitive::U32(bonus)),
+                        ),
+                    },
+                    Member {
+                        name: "position".to_string(),
+                        key: false,
+                        ty: option(
+                            Some(if position.is_some() { 0 } else { 1 }),
+                            Ty::Struct(Struct {
+                                name: "Vec2".to_string(),
+                                children: vec![
+                                    Member {
+                                        name: "x".to_string(),
+                                        key: false,
+                                        ty: Ty::Primitive(Primitive::U32(x)),
+                                    },
+                                    Member {
+                                        name: "y".to_string(),
+                                        key: false,
+                                        ty: Ty::Primitive(Primitive::U32(y)),
+                                    },
+                                ],
+                            }),
+                        ),
+                    },
+                ],
+            })
+        };
+
+        let loots = vec![
+            loot(FieldElement::ONE, Some(5), Some((3, 4))),
+            loot(FieldElement::TWO, None, None),
+        ];
+        spinup_model_test(&pool, loot(FieldElement::ONE, None, None), loots).await;
+
+        let mut conn = pool.acquire().await.unwrap();
+        let model_id = format!("{:#x}", get_selector_from_name("Loot").unwrap());
+        let type_mapping = type_mapping_query(&mut conn, &model_id).await.unwrap();
+        assert_eq!(
+            type_mapping[&Name::new("bonus")],
+            TypeData::Option(Box::new(TypeData::Simple(TypeRef::named("u32"))))
+        );
+
+        let model_data = |player: FieldElement| {
+            let type_mapping = type_mapping.clone();
+            let pool = pool.clone();
+            async move {
+                let mut conn = pool.acquire().await.unwrap();
+                let entity_id = format!("{:#x}", poseidon_hash_many(&[player]));
+                let data = model_data_recursive_query(
+                    &mut conn,
+                    vec!["Loot".to_string()],
+                    &entity_id,
+                    &[],
+                    &type_mapping,
+                )
+                .await
+                .unwrap();
+
+                let async_graphql::Value::Object(model) = data else {
+                    panic!("expected an object")
+                };
+                (model[&Name::new("bonus")].clone(), model[&Name::new("position")].clone())
+            }
+        };
+
+        // `Some` resolves to its value, nested optional structs included
+        let (bonus, position) = model_data(FieldElement::ONE).await;
+        assert_eq!(bonus, async_graphql::Value::from(5));
+        let async_graphql::Value::Object(position) = position else { panic!("expected an object") };
+        assert_eq!(position[&Name::new("x")], async_graphql::Value::from(3));
+        assert_eq!(position[&Name::new("y")], async_graphql::Value::from(4));
+
+        // `None` resolves to null
+        let (bonus, position) = model_data(FieldElement::TWO).await;
+        assert_eq!(bonus, async_graphql::Value::Null);
+        assert_eq!(position, async_graphql::Value::Null);
+
+        // options are resolved the same way by the model queries
+        let schema = build_schema(&pool).await.unwrap();
+        let result = run_graphql_query(
+            &schema,
+            r#"
+            {
+              lootModels(order: { direction: ASC, field: PLAYER }) {
+                edges {
+                  node {
+                    bonus
+                    position {
+                      x
+                      y
+                    }
+                  }
+                }
+              }
+            }
+            "#,
+        )
+        .await;
+        let nodes: Vec<Value> = result["lootModels"]["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edge| edge["node"].clone())
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                serde_json::json!({ "bonus": 5, "position": { "x": 3, "y": 4 } }),
+                serde_json::json!({ "bonus": null, "position": null }),
+            ]
+        );
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_entity_models_pagination(pool: SqlitePool) {
+        let mut db = Sql::new(pool.clone(), FieldElement::ZERO).await.unwrap();
+
+        let model = |name: &str, value: Option<u32>| {
+            Ty::Struct(Struct {
+                name: name.to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+                    },
+                    Member {
+                        name: "value".to_string(),
+                        key: false,
+                        ty: Ty::Primitive(Primitive::U32(value)),
+                    },
+                ],
+            })
+        };
+
+        // the same entity has the three models, registered out of name order
+        for name in ["Gamma", "Alpha", "Beta"] {
+            register_test_model(&mut db, model(name, None)).await;
+            db.set_entity(model(name, Some(1)), &event_id(0), 1710754478_u64).await.unwrap();
+        }
+        let schema = build_schema(&pool).await.unwrap();
+
+        let models = |arguments: &str| {
+            let query = format!(
+                "{{ entities {{ edges {{ node {{ models{} {{ __typename }} }} }} }} }}",
+                arguments
+            );
+            let schema = schema.clone();
+            async move {
+                let result = run_graphql_query(&schema, &query).await;
+                result["entities"]["edges"][0]["node"]["models"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|model| model["__typename"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // without ordering, models are returned by id
+        let mut by_id = vec!["Alpha", "Beta", "Gamma"];
+        by_id.sort_by_key(|name| format!("{:#x}", get_selector_from_name(name).unwrap()));
+        assert_eq!(models("").await, by_id);
+
+        assert_eq!(models("(orderBy: ASC)").await, vec!["Alpha", "Beta", "Gamma"]);
+        assert_eq!(models("(orderBy: DESC)").await, vec!["Gamma", "Beta", "Alpha"]);
+
+        assert_eq!(models("(orderBy: ASC, limit: 2)").await, vec!["Alpha", "Beta"]);
+        assert_eq!(models("(orderBy: DESC, limit: 2)").await, vec!["Gamma", "Beta"]);
+        assert_eq!(models("(orderBy: ASC, limit: 1, offset: 1)").await, vec!["Beta"]);
+        assert_eq!(models("(orderBy: ASC, offset: 2)").await, vec!["Gamma"]);
+        assert_eq!(models("(orderBy: ASC, limit: 5)").await.len(), 3);
+        assert!(models("(orderBy: ASC, offset: 3)").await.is_empty());
+        assert!(models("(limit: 0)").await.is_empty());
+    }
 }
@@ -209,7 +209,8 @@ pub async fn run_graphql_subscription(
 }
 
 pub async fn model_fixtures(db: &mut Sql) {
-    db.register_model(
+    register_test_model(
+        db,
         Ty::Struct(Struct {
             name: "Record".to_string(),
             children: vec![
@@ -259,6 +260,66 @@ pub async fn model_fixtures(db: &mut Sql) {
                 },
             ],
         }),
+    )
+    .await;
+}
+
+// Sets a single Record entity, keyed by `0x1`, on top of the model fixtures
+pub async fn entity_fixtures(db: &mut Sql) {
+    model_fixtures(db).await;
+
+    db.set_entity(
+        Ty::Struct(Struct {
+            name: "Record".to_string(),
+            children: vec![
+                Member {
+                    name: "depth".to_string(),
+                    key: false,
+                    ty: Ty::Enum(Enum {
+                        name: "Depth".to_string(),
+                        option: Some(0),
+                        options: vec![
+                            EnumOption { name: "Zero".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "One".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "Two".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "Three".to_string(), ty: Ty::Tuple(vec![]) },
+                        ],
+                    }),
+                },
+                Member {
+                    name: "record_id".to_string(),
+                    key: false,
+                    ty: Ty::Primitive(Primitive::U32(Some(0))),
+                },
+                Member {
+                    name: "type_felt".to_string(),
+                    key: false,
+                    ty: Ty::Primitive(Primitive::Felt252(Some(FieldElement::from(1u128)))),
+                },
+                Member {
+                    name: "typeContractAddress".to_string(),
+                    key: true,
+                    ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+                },
+            ],
+        }),
+        &event_id(0),
+        1710754478_u64,
+    )
+    .await
+    .unwrap();
+}
+
+// The id of the `idx`-th event of the fixtures
+pub fn event_id(idx: u64) -> String {
+    format!("0x{:064x}:0x{:04x}:0x{:04x}", idx, 0, 0)
+}
+
+// Registers a model, typed after the members of `model`, with the class hash `0x1` and the
+// contract address `0x2`
+pub async fn register_test_model(db: &mut Sql, model: Ty) {
+    db.register_model(
+        model,
         Layout::Fixed(vec![]),
         FieldElement::ONE,
         FieldElement::TWO,
@@ -270,6 +331,146 @@ pub async fn model_fixtures(db: &mut Sql) {
     .unwrap();
 }
 
+// Sets up a database with the Record entity of the fixtures
+pub async fn spinup_entities_test(pool: &SqlitePool) -> Sql {
+    let mut db = Sql::new(pool.clone(), FieldElement::ZERO).await.unwrap();
+    entity_fixtures(&mut db).await;
+
+    db
+}
+
+// Sets up a database with a single model and its entities, each one set by its own event
+pub async fn spinup_model_test(pool: &SqlitePool, model: Ty, entities: Vec<Ty>) -> Sql {
+    let mut db = Sql::new(pool.clone(), FieldElement::ZERO).await.unwrap();
+    register_test_model(&mut db, model).await;
+
+    for (idx, entity) in entities.into_iter().enumerate() {
+        db.set_entity(entity, &event_id(idx as u64), 1710754478_u64).await.unwrap();
+    }
+
+    db
+}
+
+// A player model, keyed by the `player` address, with a `score`
+pub fn player_model(player: u64, score: u32) -> Ty {
+    Ty::Struct(Struct {
+        name: "Player".to_string(),
+        children: vec![
+            Member {
+                name: "player".to_string(),
+                key: true,
+                ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::from(player)))),
+            },
+            Member {
+                name: "score".to_string(),
+                key: false,
+                ty: Ty::Primitive(Primitive::U32(Some(score))),
+            },
+        ],
+    })
+}
+
+// A bag model of the `player`, with a list of u32 values
+pub fn bag_model(player: FieldElement, values: Vec<u32>) -> Ty {
+    Ty::Struct(Struct {
+        name: "Bag".to_string(),
+        children: vec![
+            Member {
+                name: "player".to_string(),
+                key: true,
+                ty: Ty::Primitive(Primitive::ContractAddress(Some(player))),
+            },
+            Member {
+                name: "values".to_string(),
+                key: false,
+                ty: Ty::Array(
+                    values.into_iter().map(|v| Ty::Primitive(Primitive::U32(Some(v)))).collect(),
+                ),
+            },
+        ],
+    })
+}
+
+// An inventory model of the player `0x1`, with a list of items made of an id and a power
+pub fn inventory_model(items: &[(u32, u32)]) -> Ty {
+    let item = |(id, power): &(u32, u32)| {
+        Ty::Struct(Struct {
+            name: "Item".to_string(),
+            children: vec![
+                Member {
+                    name: "id".to_string(),
+                    key: false,
+                    ty: Ty::Primitive(Primitive::U32(Some(*id))),
+                },
+                Member {
+                    name: "power".to_string(),
+                    key: false,
+                    ty: Ty::Primitive(Primitive::U32(Some(*power))),
+                },
+            ],
+        })
+    };
+
+    Ty::Struct(Struct {
+        name: "Inventory".to_string(),
+        children: vec![
+            Member {
+                name: "player".to_string(),
+                key: true,
+                ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+            },
+            Member {
+                name: "items".to_string(),
+                key: false,
+                ty: Ty::Array(items.iter().map(item).collect()),
+            },
+        ],
+    })
+}
+
+// An action model of the player `0x1`, whose kind is either `Idle` or a `Move` to x and y
+pub fn action_model(option: Option<u8>, x: Option<u32>, y: Option<u32>) -> Ty {
+    Ty::Struct(Struct {
+        name: "Action".to_string(),
+        children: vec![
+            Member {
+                name: "player".to_string(),
+                key: true,
+                ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+            },
+            Member {
+                name: "kind".to_string(),
+                key: false,
+                ty: Ty::Enum(Enum {
+                    name: "Kind".to_string(),
+                    option,
+                    options: vec![
+                        EnumOption { name: "Idle".to_string(), ty: Ty::Tuple(vec![]) },
+                        EnumOption {
+                            name: "Move".to_string(),
+                            ty: Ty::Struct(Struct {
+                                name: "Vec2".to_string(),
+                                children: vec![
+                                    Member {
+                                        name: "x".to_string(),
+                                        key: false,
+                                        ty: Ty::Primitive(Primitive::U32(x)),
+                                    },
+                                    Member {
+                                        name: "y".to_string(),
+                                        key: false,
+                                        ty: Ty::Primitive(Primitive::U32(y)),
+                                    },
+                                ],
+                            }),
+                        },
+                    ],
+                }),
+            },
+        ],
+    })
+}
+
 pub async fn spinup_types_test() -> Result<SqlitePool> {
     // change sqlite::memory: to sqlite:~/.test.db to dump database to disk
     let options =
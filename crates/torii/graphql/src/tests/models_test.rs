@@ -3,13 +3,23 @@ mod tests {
     use std::str::FromStr;
 
     use anyhow::Result;
-    use async_graphql::dynamic::Schema;
-    use serde_json::Value;
-    use starknet_crypto::FieldElement;
-
-    use crate::schema::build_schema;
+    use async_graphql::dynamic::{Schema, TypeRef};
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
+    use serde_json::{json, Value};
+    use sqlx::SqlitePool;
+    use starknet_crypto::{poseidon_hash_many, FieldElement};
+    use torii_core::sql::Sql;
+
+    use crate::object::model_data::ComputedFields;
+    use crate::schema::{build_schema, build_schema_with_options};
     use crate::tests::{
-        run_graphql_query, spinup_types_test, Connection, Record, RecordSibling, Subrecord,
+        model_fixtures, run_graphql_query, spinup_model_test, spinup_types_test, Connection,
+        Record, RecordSibling, Subrecord,
+    };
+    use crate::types::{
+        DatetimeFormat, EntityNames, FieldCasing, ListBatchSize, ListCompression, RowCap,
+        ValueMapping,
     };
 
     async fn record_sibling_query(schema: &Schema, arg: &str) -> Value {
@@ -437,4 +447,333 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_schema(pool: SqlitePool) {
+        let member = |name: &str, ty: Ty| Member { name: name.to_string(), key: false, ty };
+        let vec2 = Ty::Struct(Struct {
+            name: "Vec2".to_string(),
+            children: vec![
+                member("x", Ty::Primitive(Primitive::U32(None))),
+                member("y", Ty::Primitive(Primitive::U32(None))),
+            ],
+        });
+        let player = Ty::Struct(Struct {
+            name: "Player".to_string(),
+            children: vec![
+                Member {
+                    name: "player".to_string(),
+                    key: true,
+                    ty: Ty::Primitive(Primitive::ContractAddress(None)),
+                },
+                member("score", Ty::Primitive(Primitive::U32(None))),
+                member("position", vec2.clone()),
+                member("items", Ty::Array(vec![Ty::Primitive(Primitive::U32(None))])),
+                member(
+                    "state",
+                    Ty::Enum(Enum {
+                        name: "State".to_string(),
+                        option: None,
+                        options: vec![
+                            EnumOption { name: "Alive".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "Dead".to_string(), ty: Ty::Tuple(vec![]) },
+                        ],
+                    }),
+                ),
+                member(
+                    "action",
+                    Ty::Enum(Enum {
+                        name: "Action".to_string(),
+                        option: None,
+                        options: vec![
+                            EnumOption { name: "Idle".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "Move".to_string(), ty: vec2 },
+                        ],
+                    }),
+                ),
+            ],
+        });
+
+        spinup_model_test(&pool, player, vec![]).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        let result =
+            run_graphql_query(&schema, r#"{ modelSchema(name: "Player") { name type kind } }"#)
+                .await;
+        let fields = result["modelSchema"].as_array().unwrap();
+
+        let kinds: Vec<(&str, &str)> = fields
+            .iter()
+            .map(|field| (field["name"].as_str().unwrap(), field["kind"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("player", "SCALAR"),
+                ("score", "SCALAR"),
+                ("position", "NESTED"),
+                ("items", "LIST"),
+                ("state", "UNION"),
+                ("action", "UNION"),
+            ]
+        );
+        assert_eq!(fields[1]["type"], "u32");
+        assert_eq!(fields[3]["type"], "[u32]");
+
+        // unknown models have no schema
+        let result =
+            run_graphql_query(&schema, r#"{ modelSchema(name: "Unknown") { name } }"#).await;
+        assert_eq!(result["modelSchema"], json!(null));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_class_hash(pool: SqlitePool) {
+        let mut db = Sql::new(pool.clone(), FieldElement::ZERO).await.unwrap();
+        model_fixtures(&mut db).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        // the class hash and the contract address of the models are the ones they were
+        // registered with
+        let result = run_graphql_query(
+            &schema,
+            r#"{ models { edges { node { name classHash contractAddress } } } }"#,
+        )
+        .await;
+        assert_eq!(
+            result["models"]["edges"][0]["node"],
+            json!({ "name": "Record", "classHash": "0x1", "contractAddress": "0x2" })
+        );
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_computed_field(pool: SqlitePool) {
+        let position = |x: Option<u32>, y: Option<u32>| {
+            Ty::Struct(Struct {
+                name: "Position".to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+                    },
+                    Member {
+                        name: "x".to_string(),
+                        key: false,
+                        ty: Ty::Primitive(Primitive::U32(x)),
+                    },
+                    Member {
+                        name: "y".to_string(),
+                        key: false,
+                        ty: Ty::Primitive(Primitive::U32(y)),
+                    },
+                ],
+            })
+        };
+
+        spinup_model_test(&pool, position(None, None), vec![position(Some(3), Some(4))]).await;
+
+        let distance = |position: &ValueMapping| {
+            let coordinate = |name: &str| match position.get(name) {
+                Some(async_graphql::Value::Number(value)) => value.as_f64().unwrap_or_default(),
+                _ => 0.0,
+            };
+            async_graphql::Value::from(coordinate("x").hypot(coordinate("y")))
+        };
+        let mut computed_fields = ComputedFields::default();
+        computed_fields.register("Position", "distance", TypeRef::named(TypeRef::FLOAT), distance);
+        let schema = build_schema_with_options(
+            &pool,
+            DatetimeFormat::default(),
+            RowCap::default(),
+            EntityNames::default(),
+            computed_fields,
+            FieldCasing::default(),
+            ListCompression::default(),
+            ListBatchSize::default(),
+        )
+        .await
+        .unwrap();
+
+        let result =
+            run_graphql_query(&schema, "{ positionModels { edges { node { x distance } } } }")
+                .await;
+        assert_eq!(
+            result["positionModels"]["edges"][0]["node"],
+            json!({ "x": 3, "distance": 5.0 })
+        );
+
+        // the computed fields are resolved for the models of an entity too
+        let result = run_graphql_query(
+            &schema,
+            &format!(
+                r#"{{ entity(id: "{:#x}") {{ models {{ ... on Position {{ distance }} }} }} }}"#,
+                poseidon_hash_many(&[FieldElement::ONE])
+            ),
+        )
+        .await;
+        assert_eq!(result["entity"]["models"][0], json!({ "distance": 5.0 }));
+
+        // a computed field can't shadow a field of the model
+        let mut computed_fields = ComputedFields::default();
+        computed_fields.register("Position", "x", TypeRef::named(TypeRef::FLOAT), distance);
+        assert!(build_schema_with_options(
+            &pool,
+            DatetimeFormat::default(),
+            RowCap::default(),
+            EntityNames::default(),
+            computed_fields,
+            FieldCasing::default(),
+            ListCompression::default(),
+            ListBatchSize::default(),
+        )
+        .await
+        .is_err());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_entity_ids(pool: SqlitePool) {
+        let position = |player: u8, x: u32| {
+            Ty::Struct(Struct {
+                name: "Position".to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::from(
+                            player,
+                        )))),
+                    },
+                    Member {
+                        name: "x".to_string(),
+                        key: false,
+                        ty: Ty::Primitive(Primitive::U32(Some(x))),
+                    },
+                ],
+            })
+        };
+
+        let positions = vec![position(1, 10), position(2, 20), position(3, 30)];
+        spinup_model_test(&pool, position(0, 0), positions).await;
+        let schema = build_schema(&pool).await.unwrap();
+
+        // the rows of the model are queried across the entities, with their entity ids
+        let result = run_graphql_query(
+            &schema,
+            r#"{
+                positionModels(where: { xGT: 10 }, order: { field: X, direction: ASC }) {
+                    totalCount
+                    edges { node { x entityId entity { id } } }
+                }
+            }"#,
+        )
+        .await;
+        let entity_id = |player: u8| format!("{:#x}", poseidon_hash_many(&[player.into()]));
+        assert_eq!(result["positionModels"]["totalCount"], json!(2));
+        assert_eq!(
+            result["positionModels"]["edges"],
+            json!([
+                { "node": { "x": 20, "entityId": entity_id(2), "entity": { "id": entity_id(2) } } },
+                { "node": { "x": 30, "entityId": entity_id(3), "entity": { "id": entity_id(3) } } },
+            ])
+        );
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_model_camel_case_fields(pool: SqlitePool) {
+        let score = |player_score: u32, move_count: u8| {
+            Ty::Struct(Struct {
+                name: "Score".to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+                    },
+                    Member {
+                        name: "player_score".to_string(),
+                        key: false,
+                        ty: Ty::Primitive(Primitive::U32(Some(player_score))),
+                    },
+                    Member {
+                        name: "last_move".to_string(),
+                        key: false,
+                        ty: Ty::Struct(Struct {
+                            name: "Move".to_string(),
+                            children: vec![Member {
+                                name: "move_count".to_string(),
+                                key: false,
+                                ty: Ty::Primitive(Primitive::U8(Some(move_count))),
+                            }],
+                        }),
+                    },
+                ],
+            })
+        };
+
+        spinup_model_test(&pool, score(0, 0), vec![score(10, 2)]).await;
+
+        // the fields are named after the members by default
+        let schema = build_schema(&pool).await.unwrap();
+        let result = run_graphql_query(
+            &schema,
+            "{ scoreModels(where: { player_scoreGT: 5 }) { edges { node { player_score } } } }",
+        )
+        .await;
+        assert_eq!(result["scoreModels"]["edges"][0]["node"], json!({ "player_score": 10 }));
+
+        let schema = build_schema_with_options(
+            &pool,
+            DatetimeFormat::default(),
+            RowCap::default(),
+            EntityNames::default(),
+            ComputedFields::default(),
+            FieldCasing::Camel,
+            ListCompression::default(),
+            ListBatchSize::default(),
+        )
+        .await
+        .unwrap();
+
+        // the fields of the nested structs and the where inputs are cased too
+        let query = r#"
+          {
+            scoreModels(where: { playerScoreGT: 5 }) {
+              edges {
+                node {
+                  playerScore
+                  lastMove {
+                    moveCount
+                  }
+                }
+              }
+            }
+          }
+        "#;
+        let result = run_graphql_query(&schema, query).await;
+        assert_eq!(
+            result["scoreModels"]["edges"][0]["node"],
+            json!({ "playerScore": 10, "lastMove": { "moveCount": 2 } })
+        );
+
+        let result = run_graphql_query(
+            &schema,
+            "{ scoreModels(where: { playerScoreLT: 5 }) { edges { node { playerScore } } } }",
+        )
+        .await;
+        assert_eq!(result["scoreModels"]["edges"], json!([]));
+
+        // only the selected fields of the models of an entity are fetched, by their member names
+        let result = run_graphql_query(
+            &schema,
+            &format!(
+                r#"{{ entity(id: "{:#x}") {{ models {{ ... on Score {{ playerScore }} }} }} }}"#,
+                poseidon_hash_many(&[FieldElement::ONE])
+            ),
+        )
+        .await;
+        assert_eq!(result["entity"]["models"][0], json!({ "playerScore": 10 }));
+
+        let result = schema.execute("{ scoreModels { edges { node { player_score } } } }").await;
+        assert_eq!(result.errors.len(), 1);
+    }
 }
@@ -4,6 +4,7 @@ mod tests {
     use std::time::Duration;
 
     use async_graphql::value;
+    use chrono::{TimeZone, Utc};
     use dojo_types::primitive::Primitive;
     use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
     use dojo_world::contracts::abi::model::Layout;
@@ -13,9 +14,13 @@ mod tests {
     use starknet::core::utils::get_selector_from_name;
     use starknet_crypto::{poseidon_hash_many, FieldElement};
     use tokio::sync::mpsc;
+    use tokio_stream::StreamExt;
+    use torii_core::simple_broker::SimpleBroker;
     use torii_core::sql::Sql;
+    use torii_core::types::Entity;
 
-    use crate::tests::{model_fixtures, run_graphql_subscription};
+    use crate::schema::build_schema;
+    use crate::tests::{entity_fixtures, model_fixtures, run_graphql_subscription};
 
     #[sqlx::test(migrations = "../migrations")]
     #[serial]
@@ -242,6 +247,250 @@ mod tests {
         rx.recv().await.unwrap();
     }
 
+    #[sqlx::test(migrations = "../migrations")]
+    #[serial]
+    async fn test_entity_subscription_with_keys(pool: SqlitePool) {
+        let entity = |id: &str, keys: &str| {
+            let datetime = Utc.with_ymd_and_hms(2024, 3, 18, 9, 34, 38).unwrap();
+            Entity {
+                id: id.to_string(),
+                keys: keys.to_string(),
+                event_id: "0x0:0x0:0x0".to_string(),
+                executed_at: datetime,
+                created_at: datetime,
+                updated_at: datetime,
+                model: "Record".to_string(),
+            }
+        };
+        let (tx, mut rx) = mpsc::channel(10);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            // only the last entity matches the keys of the subscription
+            SimpleBroker::publish(entity("0x1", "0x2/0x3/"));
+            SimpleBroker::publish(entity("0x2", "0x1/"));
+            SimpleBroker::publish(entity("0x3", "0x1/0x3/"));
+
+            tx.send(()).await.unwrap();
+        });
+
+        let response_value = run_graphql_subscription(
+            &pool,
+            r#"subscription {
+                entityUpdated(keys: ["0x1", "*"]) {
+                    id
+                    keys
+                }
+            }"#,
+        )
+        .await;
+
+        let expected_value: async_graphql::Value = value!({
+            "entityUpdated": { "id": "0x3", "keys": vec!["0x1", "0x3"] }
+        });
+        assert_eq!(expected_value, response_value);
+        rx.recv().await.unwrap();
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    #[serial]
+    async fn test_entity_subscription_with_model(pool: SqlitePool) {
+        let entity = |id: &str, model: &str| {
+            let datetime = Utc.with_ymd_and_hms(2024, 3, 18, 9, 34, 38).unwrap();
+            Entity {
+                id: id.to_string(),
+                keys: "0x1/".to_string(),
+                event_id: "0x0:0x0:0x0".to_string(),
+                executed_at: datetime,
+                created_at: datetime,
+                updated_at: datetime,
+                model: model.to_string(),
+            }
+        };
+        let (tx, mut rx) = mpsc::channel(10);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            // the entity set with another model doesn't reach the subscription
+            SimpleBroker::publish_scoped("Position", entity("0x1", "Position"));
+            SimpleBroker::publish_scoped("Moves", entity("0x2", "Moves"));
+
+            tx.send(()).await.unwrap();
+        });
+
+        let response_value = run_graphql_subscription(
+            &pool,
+            r#"subscription {
+                entityUpdated(model: "Moves") {
+                    id
+                }
+            }"#,
+        )
+        .await;
+
+        let expected_value: async_graphql::Value = value!({
+            "entityUpdated": { "id": "0x2" }
+        });
+        assert_eq!(expected_value, response_value);
+        rx.recv().await.unwrap();
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    #[serial]
+    async fn test_entity_subscription_delta(pool: SqlitePool) {
+        let mut db = Sql::new(pool.clone(), FieldElement::ZERO).await.unwrap();
+
+        model_fixtures(&mut db).await;
+        let entity_id = format!("{:#x}", poseidon_hash_many(&[FieldElement::ONE]));
+        let record = |type_u16: u16| {
+            let member = |name: &str, key: bool, ty: Ty| Member { name: name.to_string(), key, ty };
+            Ty::Struct(Struct {
+                name: "Record".to_string(),
+                children: vec![
+                    member(
+                        "depth",
+                        false,
+                        Ty::Enum(Enum {
+                            name: "Depth".to_string(),
+                            option: Some(0),
+                            options: vec![
+                                EnumOption { name: "Zero".to_string(), ty: Ty::Tuple(vec![]) },
+                                EnumOption { name: "One".to_string(), ty: Ty::Tuple(vec![]) },
+                                EnumOption { name: "Two".to_string(), ty: Ty::Tuple(vec![]) },
+                                EnumOption { name: "Three".to_string(), ty: Ty::Tuple(vec![]) },
+                            ],
+                        }),
+                    ),
+                    member("record_id", false, Ty::Primitive(Primitive::U32(Some(0)))),
+                    member("typeU16", false, Ty::Primitive(Primitive::U16(Some(type_u16)))),
+                    member("type_u64", false, Ty::Primitive(Primitive::U64(Some(1)))),
+                    member("typeBool", false, Ty::Primitive(Primitive::Bool(Some(true)))),
+                    member(
+                        "type_felt",
+                        false,
+                        Ty::Primitive(Primitive::Felt252(Some(FieldElement::ONE))),
+                    ),
+                    member(
+                        "typeContractAddress",
+                        true,
+                        Ty::Primitive(Primitive::ContractAddress(Some(FieldElement::ONE))),
+                    ),
+                ],
+            })
+        };
+        let (tx, mut rx) = mpsc::channel(10);
+        let (next_tx, mut next_rx) = mpsc::channel(10);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            db.set_entity(record(1), &format!("0x{:064x}:0x{:04x}:0x{:04x}", 0, 0, 0), 1710754478)
+                .await
+                .unwrap();
+
+            // the entity is updated once its first update has been sent
+            next_rx.recv().await.unwrap();
+            db.set_entity(record(2), &format!("0x{:064x}:0x{:04x}:0x{:04x}", 1, 0, 0), 1710754479)
+                .await
+                .unwrap();
+
+            tx.send(()).await.unwrap();
+        });
+
+        let schema = build_schema(&pool).await.unwrap();
+        let mut stream = schema.execute_stream(
+            r#"subscription {
+                entityUpdated(delta: true) {
+                    id
+                    models {
+                        __typename
+                        ... on Record {
+                            typeU16
+                            type_u64
+                        }
+                    }
+                }
+            }"#,
+        );
+
+        // the entity is sent whole the first time
+        let response_value = stream.next().await.unwrap().into_result().unwrap().data;
+        let expected_value: async_graphql::Value = value!({
+            "entityUpdated": {
+                "id": entity_id.clone(),
+                "models": [{ "__typename": "Record", "typeU16": 1, "type_u64": "0x1" }]
+            }
+        });
+        assert_eq!(expected_value, response_value);
+        next_tx.send(()).await.unwrap();
+
+        // then only the field which changed is sent, the other ones being null
+        let response_value = stream.next().await.unwrap().into_result().unwrap().data;
+        let expected_value: async_graphql::Value = value!({
+            "entityUpdated": {
+                "id": entity_id,
+                "models": [{ "__typename": "Record", "typeU16": 2, "type_u64": null }]
+            }
+        });
+        assert_eq!(expected_value, response_value);
+        rx.recv().await.unwrap();
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    #[serial]
+    async fn test_entity_deleted_subscription(pool: SqlitePool) {
+        let mut db = Sql::new(pool.clone(), FieldElement::ZERO).await.unwrap();
+
+        entity_fixtures(&mut db).await;
+        // 0. Preprocess expected entity value
+        let key = vec![FieldElement::ONE];
+        let entity_id = format!("{:#x}", poseidon_hash_many(&key));
+        let keys_str = key.iter().map(|k| format!("{:#x}", k)).collect::<Vec<String>>().join(",");
+        let expected_value: async_graphql::Value = value!({
+            "entityDeleted": {
+                "id": entity_id.clone(),
+                "keys": vec![keys_str],
+            }
+        });
+        let (tx, mut rx) = mpsc::channel(10);
+
+        tokio::spawn(async move {
+            // 1. Open process and sleep.Go to execute subscription
+            tokio::time::sleep(Duration::from_secs(1)).await;
+
+            // Delete the Record model of the entity
+            db.delete_entity(
+                key,
+                Ty::Struct(Struct { name: "Record".to_string(), children: vec![] }),
+            )
+            .await
+            .unwrap();
+
+            tx.send(()).await.unwrap();
+        });
+
+        // 2. The subscription is executed and it is listening, waiting for publish() to be executed
+        let schema = build_schema(&pool).await.unwrap();
+        let mut stream = schema.execute_stream(format!(
+            r#"subscription {{
+                entityDeleted(id: "{}") {{
+                    id
+                    keys
+                }}
+            }}"#,
+            entity_id
+        ));
+        let response_value = stream.next().await.unwrap().into_result().unwrap().data;
+        // 3. The subscription has received the message from publish()
+        // 4. Compare values
+        assert_eq!(expected_value, response_value);
+        rx.recv().await.unwrap();
+
+        // only a single deletion is published
+        assert!(tokio::time::timeout(Duration::from_millis(500), stream.next()).await.is_err());
+    }
+
     #[sqlx::test(migrations = "../migrations")]
     #[serial]
     async fn test_model_subscription(pool: SqlitePool) {
@@ -252,8 +501,13 @@ mod tests {
         let class_hash = FieldElement::TWO;
         let contract_address = FieldElement::THREE;
         let block_timestamp: u64 = 1710754478_u64;
+        // the model is identified by its selector, along with its name and class hash
         let expected_value: async_graphql::Value = value!({
-         "modelRegistered": { "id": model_id, "name":model_name }
+         "modelRegistered": {
+             "id": model_id,
+             "name": model_name,
+             "classHash": format!("{:#x}", class_hash)
+         }
         });
         let (tx, mut rx) = mpsc::channel(7);
 
@@ -292,7 +546,7 @@ mod tests {
             r#"
                 subscription {
                     modelRegistered {
-                            id, name
+                            id, name, classHash
                         }
                 }"#,
         )
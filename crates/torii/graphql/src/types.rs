@@ -1,10 +1,15 @@
+use anyhow::anyhow;
 use async_graphql::dynamic::indexmap::IndexMap;
 use async_graphql::dynamic::TypeRef;
 use async_graphql::{Name, Value};
+use chrono::{DateTime, Utc};
+use convert_case::{Case, Casing};
 use dojo_types::primitive::Primitive;
 use strum::IntoEnumIterator;
 use strum_macros::{AsRefStr, Display, EnumIter, EnumString};
 
+use crate::constants::{DATETIME_FORMAT, DEFAULT_LIST_BATCH_SIZE, ENTITY_NAMES, MAX_LIMIT};
+
 // ValueMapping is used to map the values of the fields of a model and TypeMapping their
 // correpsonding types. Both are used at runtime to dynamically build/resolve graphql
 // queries/schema. `Value` from async-graphql supports nesting, but TypeRef does not. TypeData is
@@ -12,21 +17,145 @@ use strum_macros::{AsRefStr, Display, EnumIter, EnumString};
 pub type ValueMapping = IndexMap<Name, Value>;
 pub type TypeMapping = IndexMap<Name, TypeData>;
 
+// Output format of the datetimes resolved from torii-core types (eg in subscriptions). The legacy
+// format is kept as the default, RFC 3339 makes the UTC offset explicit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DatetimeFormat {
+    #[default]
+    Legacy,
+    Rfc3339,
+}
+
+impl DatetimeFormat {
+    pub fn format(&self, datetime: &DateTime<Utc>) -> String {
+        match self {
+            DatetimeFormat::Legacy => datetime.format(DATETIME_FORMAT).to_string(),
+            DatetimeFormat::Rfc3339 => datetime.to_rfc3339(),
+        }
+    }
+}
+
+// Casing of the names of the model fields in the schema. The fields are named after the Cairo
+// members (ie snake_case) by default, camelCase following the GraphQL conventions. The data is
+// still stored and looked up by the member names
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldCasing {
+    #[default]
+    Snake,
+    Camel,
+}
+
+impl FieldCasing {
+    pub fn apply(&self, name: &str) -> String {
+        match self {
+            FieldCasing::Snake => name.to_string(),
+            FieldCasing::Camel => name.to_case(Case::Camel),
+        }
+    }
+}
+
+// Maximum number of rows fetched by a connection, whatever its pagination arguments. Larger
+// requests are truncated to the cap, so that a single query cannot load the whole indexer database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowCap(pub u64);
+
+impl Default for RowCap {
+    fn default() -> Self {
+        Self(MAX_LIMIT)
+    }
+}
+
+// Number of rows of the tables nested in the lists of the models fetched by each query, the rows
+// being decoded batch by batch so that only one batch is held in memory whatever the list lengths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListBatchSize(pub u64);
+
+impl Default for ListBatchSize {
+    fn default() -> Self {
+        Self(DEFAULT_LIST_BATCH_SIZE)
+    }
+}
+
+// Size (of their JSON, in bytes) above which the lists of scalars of the models are compressed in
+// their `{name}_compressed` field: `COMPRESSED_LIST_PREFIX` followed by the gzipped JSON of the
+// list encoded in base64. The fields are only added to the schema when the lists are compressed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ListCompression(pub Option<usize>);
+
+// Names of the queries fetching one and many entities, falling back to `ENTITY_NAMES` so that
+// the schema can follow the naming conventions of an existing one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityNames {
+    one: String,
+    many: String,
+}
+
+impl Default for EntityNames {
+    fn default() -> Self {
+        Self { one: ENTITY_NAMES.0.to_string(), many: ENTITY_NAMES.1.to_string() }
+    }
+}
+
+impl EntityNames {
+    pub fn new(one: Option<String>, many: Option<String>) -> anyhow::Result<Self> {
+        let default = Self::default();
+        let names = Self { one: one.unwrap_or(default.one), many: many.unwrap_or(default.many) };
+
+        for name in [&names.one, &names.many] {
+            if !is_graphql_name(name) {
+                return Err(anyhow!("'{}' is not a valid GraphQL name for an entity query.", name));
+            }
+        }
+
+        if names.one == names.many {
+            return Err(anyhow!(
+                "The queries of one and many entities can't have the same name '{}'.",
+                names.one
+            ));
+        }
+
+        Ok(names)
+    }
+
+    pub fn one(&self) -> &str {
+        &self.one
+    }
+
+    pub fn many(&self) -> &str {
+        &self.many
+    }
+}
+
+// GraphQL names are made of letters, digits and underscores, and don't start with a digit. The
+// names starting with two underscores are reserved for the introspection
+fn is_graphql_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.starts_with("__")
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TypeData {
     Simple(TypeRef),
     Nested((TypeRef, IndexMap<Name, TypeData>)),
     List(Box<TypeData>),
-    // Union can only  represent an object of objects
-    // Enum((TypeRef, IndexMap<Name, TypeData>)),
+    // Option<T>, resolved to the value of its `Some` variant or to null
+    Option(Box<TypeData>),
+    // Enum holding data, an object with an `option` field naming the active variant and a field
+    // per variant
+    Enum((TypeRef, IndexMap<Name, TypeData>)),
 }
 
 impl TypeData {
     pub fn type_ref(&self) -> TypeRef {
         match self {
-            TypeData::Simple(ty) | TypeData::Nested((ty, _)) => ty.clone(),
+            TypeData::Simple(ty) | TypeData::Nested((ty, _)) | TypeData::Enum((ty, _)) => {
+                ty.clone()
+            }
             TypeData::List(inner) => TypeRef::List(Box::new(inner.type_ref())),
-            // TypeData::Enum((ty, _)) => ty.clone(),
+            TypeData::Option(inner) => inner.type_ref(),
         }
     }
 
@@ -34,24 +163,31 @@ impl TypeData {
         matches!(self, TypeData::Simple(_))
     }
 
+    // Enums holding data are nested objects as well
     pub fn is_nested(&self) -> bool {
-        matches!(self, TypeData::Nested(_))
+        matches!(self, TypeData::Nested(_) | TypeData::Enum(_))
     }
 
     pub fn is_list(&self) -> bool {
         matches!(self, TypeData::List(_))
     }
 
-    // pub fn is_enum(&self) -> bool {
-    //     matches!(self, TypeData::Enum(_))
-    // }
+    pub fn is_option(&self) -> bool {
+        matches!(self, TypeData::Option(_))
+    }
+
+    pub fn is_enum(&self) -> bool {
+        matches!(self, TypeData::Enum(_))
+    }
 
     pub fn type_mapping(&self) -> Option<&IndexMap<Name, TypeData>> {
         match self {
             TypeData::Simple(_) => None,
-            TypeData::Nested((_, type_mapping)) => Some(type_mapping),
+            TypeData::Nested((_, type_mapping)) | TypeData::Enum((_, type_mapping)) => {
+                Some(type_mapping)
+            }
             TypeData::List(_) => None,
-            // TypeData::Enum((_, type_mapping)) => Some(type_mapping),
+            TypeData::Option(_) => None,
         }
     }
 }
@@ -0,0 +1,15 @@
+-- Append-only log of the events which set the entities, the entities table only keeping the last one
+CREATE TABLE entity_history (
+    entity_id TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    executed_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (entity_id, event_id),
+    FOREIGN KEY (entity_id) REFERENCES entities (id)
+);
+CREATE INDEX idx_entity_history_entity_id ON entity_history (entity_id);
+
+-- The history of the entities indexed before starts with their last event
+INSERT INTO entity_history (entity_id, event_id, executed_at)
+SELECT id, event_id, executed_at
+FROM entities;
@@ -102,15 +102,37 @@ mod actions {
 
 #[cfg(test)]
 mod tests {
+    use starknet::ContractAddress;
     use starknet::class_hash::Felt252TryIntoClassHash;
 
-    use dojo::world::{IWorldDispatcher, IWorldDispatcherTrait};
+    use dojo::world::{
+        IWorldDispatcher, IWorldDispatcherTrait, IWorldProviderDispatcher,
+        IWorldProviderDispatcherTrait, IDojoVersionProviderDispatcher,
+        IDojoVersionProviderDispatcherTrait, IPausableDispatcher, IPausableDispatcherTrait
+    };
 
     use dojo::test_utils::{spawn_test_world, deploy_contract};
 
     use super::{actions, IActionsDispatcher, IActionsDispatcherTrait};
     use dojo_examples::models::{Position, position, Moves, moves, Direction, Vec2};
 
+    #[dojo::interface]
+    trait IPausableActions {
+        fn spawn();
+    }
+
+    #[dojo::contract(pausable: true)]
+    mod pausable_actions {
+        use super::IPausableActions;
+
+        #[abi(embed_v0)]
+        impl PausableActionsImpl of IPausableActions<ContractState> {
+            fn spawn(world: IWorldDispatcher) {
+                self.when_not_paused();
+            }
+        }
+    }
+
     #[test]
     #[available_gas(30000000)]
     fn test_move() {
@@ -141,4 +163,72 @@ mod tests {
         assert(new_position.vec.x == 11, 'position x is wrong');
         assert(new_position.vec.y == 10, 'position y is wrong');
     }
+
+    #[test]
+    #[available_gas(30000000)]
+    fn test_world_address() {
+        let world = spawn_test_world(array![]);
+
+        let contract_address = world
+            .deploy_contract('salt', actions::TEST_CLASS_HASH.try_into().unwrap());
+        let world_provider = IWorldProviderDispatcher { contract_address };
+
+        assert(world_provider.world_address() == world.contract_address, 'world address is wrong');
+    }
+
+    #[test]
+    #[available_gas(30000000)]
+    fn test_dojo_version() {
+        let world = spawn_test_world(array![]);
+
+        let contract_address = world
+            .deploy_contract('salt', actions::TEST_CLASS_HASH.try_into().unwrap());
+        let version_provider = IDojoVersionProviderDispatcher { contract_address };
+
+        assert(version_provider.dojo_version() == 1, 'dojo version is wrong');
+    }
+
+    fn deploy_pausable_actions() -> (IWorldDispatcher, ContractAddress) {
+        let world = spawn_test_world(array![]);
+        let contract_address = world
+            .deploy_contract('salt', pausable_actions::TEST_CLASS_HASH.try_into().unwrap());
+        (world, contract_address)
+    }
+
+    #[test]
+    #[available_gas(30000000)]
+    fn test_unpause() {
+        let (world, contract_address) = deploy_pausable_actions();
+        let pausable = IPausableDispatcher { contract_address };
+
+        // only the world can pause and unpause its contracts
+        starknet::testing::set_contract_address(world.contract_address);
+        pausable.pause();
+        assert(pausable.is_paused(), 'contract should be paused');
+
+        pausable.unpause();
+        assert(!pausable.is_paused(), 'contract should not be paused');
+        IPausableActionsDispatcher { contract_address }.spawn();
+    }
+
+    #[test]
+    #[available_gas(30000000)]
+    #[should_panic(expected: ('contract is paused', 'ENTRYPOINT_FAILED'))]
+    fn test_paused_system() {
+        let (world, contract_address) = deploy_pausable_actions();
+
+        starknet::testing::set_contract_address(world.contract_address);
+        IPausableDispatcher { contract_address }.pause();
+        IPausableActionsDispatcher { contract_address }.spawn();
+    }
+
+    #[test]
+    #[available_gas(30000000)]
+    #[should_panic(expected: ('must be called by world', 'ENTRYPOINT_FAILED'))]
+    fn test_pause_not_world() {
+        let (_, contract_address) = deploy_pausable_actions();
+
+        starknet::testing::set_contract_address(starknet::contract_address_const::<0xb0b>());
+        IPausableDispatcher { contract_address }.pause();
+    }
 }